@@ -157,6 +157,7 @@ create_aggregation! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -247,6 +248,7 @@ create_aggregation! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -279,6 +281,7 @@ create_aggregation! {
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert!(value.is_normal());
@@ -397,6 +400,7 @@ pub fn topk(vector: InstantVector, labels: Option<Aggregate<'_>>, parameter: u64
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert!(value.is_normal());
@@ -0,0 +1,884 @@
+//! A client for the [Alertmanager API v2](https://github.com/prometheus/alertmanager/blob/main/api/v2/openapi.yaml)
+//! that lists alerts, manages silences and reads the status of Alertmanagers discovered
+//! by Prometheus (see [crate::Client::alertmanagers]).
+//!
+//! ```rust
+//! use prometheus_http_query::alertmanager::Alertmanager;
+//! use prometheus_http_query::{Client, Error};
+//! use std::time::Duration;
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let client = Client::default();
+//!
+//!     let alertmanagers = client.alertmanagers().await?;
+//!
+//!     if let Some(url) = alertmanagers.active().first() {
+//!         let alertmanager = Alertmanager::from(client.inner().clone(), url.as_str())?;
+//!
+//!         for alert in client.alerts().await? {
+//!             if alert.state().to_string() == "firing" {
+//!                 let id = alertmanager
+//!                     .silence_alert(&alert, Duration::from_secs(3600), "ops", "maintenance")
+//!                     .await?;
+//!                 println!("created silence {}", id);
+//!             }
+//!         }
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::error::{AlertmanagerError, Error};
+use crate::exposition;
+use crate::response::de::deserialize_rfc3339;
+use crate::response::ser::serialize_rfc3339;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use time::OffsetDateTime;
+
+/// A label matcher as used by silences and to filter alerts and silences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Matcher {
+    pub(crate) name: String,
+    pub(crate) value: String,
+    #[serde(rename = "isRegex")]
+    pub(crate) is_regex: bool,
+    #[serde(rename = "isEqual")]
+    #[serde(default = "default_is_equal")]
+    pub(crate) is_equal: bool,
+}
+
+fn default_is_equal() -> bool {
+    true
+}
+
+impl Matcher {
+    /// Match labels that are equal to the value (`name="value"`).
+    pub fn equal(name: &str, value: &str) -> Self {
+        Matcher::build(name, value, false, true)
+    }
+
+    /// Match labels that are not equal to the value (`name!="value"`).
+    pub fn not_equal(name: &str, value: &str) -> Self {
+        Matcher::build(name, value, false, false)
+    }
+
+    /// Match labels that match the regular expression (`name=~"value"`).
+    pub fn regex_match(name: &str, value: &str) -> Self {
+        Matcher::build(name, value, true, true)
+    }
+
+    /// Match labels that do not match the regular expression (`name!~"value"`).
+    pub fn no_regex_match(name: &str, value: &str) -> Self {
+        Matcher::build(name, value, true, false)
+    }
+
+    /// Create a set of matchers that match a set of labels exactly, e.g. those of an
+    /// alert as returned by [crate::Client::alerts].
+    pub fn from_labels(labels: &HashMap<String, String>) -> Vec<Matcher> {
+        let mut matchers: Vec<Matcher> = labels
+            .iter()
+            .map(|(name, value)| Matcher::equal(name, value))
+            .collect();
+        matchers.sort_by(|a, b| a.name.cmp(&b.name));
+        matchers
+    }
+
+    fn build(name: &str, value: &str, is_regex: bool, is_equal: bool) -> Self {
+        Matcher {
+            name: name.to_string(),
+            value: value.to_string(),
+            is_regex,
+            is_equal,
+        }
+    }
+
+    /// Get the label name of this matcher.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the value or regular expression of this matcher.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Check whether the value is a regular expression.
+    pub fn is_regex(&self) -> bool {
+        self.is_regex
+    }
+
+    /// Check whether this matcher matches equal values (`=`, `=~`) or unequal ones (`!=`, `!~`).
+    pub fn is_equal(&self) -> bool {
+        self.is_equal
+    }
+}
+
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match (self.is_equal, self.is_regex) {
+            (true, false) => "=",
+            (false, false) => "!=",
+            (true, true) => "=~",
+            (false, true) => "!~",
+        };
+        write!(
+            f,
+            "{}{}\"{}\"",
+            self.name,
+            operator,
+            exposition::escape(&self.value, true)
+        )
+    }
+}
+
+/// Possible states of an alert in the Alertmanager.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+pub enum AlertmanagerAlertState {
+    #[serde(alias = "unprocessed")]
+    Unprocessed,
+    #[serde(alias = "active")]
+    Active,
+    #[serde(alias = "suppressed")]
+    Suppressed,
+}
+
+impl fmt::Display for AlertmanagerAlertState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertmanagerAlertState::Unprocessed => write!(f, "unprocessed"),
+            AlertmanagerAlertState::Active => write!(f, "active"),
+            AlertmanagerAlertState::Suppressed => write!(f, "suppressed"),
+        }
+    }
+}
+
+/// The status of an alert in the Alertmanager.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AlertStatus {
+    pub(crate) state: AlertmanagerAlertState,
+    #[serde(alias = "silencedBy")]
+    #[serde(default)]
+    pub(crate) silenced_by: Vec<String>,
+    #[serde(alias = "inhibitedBy")]
+    #[serde(default)]
+    pub(crate) inhibited_by: Vec<String>,
+}
+
+impl AlertStatus {
+    /// Get the state of this alert.
+    pub fn state(&self) -> AlertmanagerAlertState {
+        self.state
+    }
+
+    /// Get the IDs of the silences that silence this alert.
+    pub fn silenced_by(&self) -> &[String] {
+        &self.silenced_by
+    }
+
+    /// Get the fingerprints of the alerts that inhibit this alert.
+    pub fn inhibited_by(&self) -> &[String] {
+        &self.inhibited_by
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct Receiver {
+    name: String,
+}
+
+/// An alert as stored by the Alertmanager.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Alert {
+    pub(crate) fingerprint: String,
+    pub(crate) labels: HashMap<String, String>,
+    #[serde(default)]
+    pub(crate) annotations: HashMap<String, String>,
+    #[serde(alias = "startsAt")]
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub(crate) starts_at: OffsetDateTime,
+    #[serde(alias = "endsAt")]
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub(crate) ends_at: OffsetDateTime,
+    #[serde(alias = "updatedAt")]
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub(crate) updated_at: OffsetDateTime,
+    #[serde(alias = "generatorURL")]
+    #[serde(default)]
+    pub(crate) generator_url: String,
+    receivers: Vec<Receiver>,
+    pub(crate) status: AlertStatus,
+}
+
+impl Alert {
+    /// Get the fingerprint that identifies this alert.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Get the set of labels of this alert.
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// Get the set of annotations of this alert.
+    pub fn annotations(&self) -> &HashMap<String, String> {
+        &self.annotations
+    }
+
+    /// Get the time when this alert started firing.
+    pub fn starts_at(&self) -> &OffsetDateTime {
+        &self.starts_at
+    }
+
+    /// Get the time when this alert is considered resolved.
+    pub fn ends_at(&self) -> &OffsetDateTime {
+        &self.ends_at
+    }
+
+    /// Get the time when this alert was last updated.
+    pub fn updated_at(&self) -> &OffsetDateTime {
+        &self.updated_at
+    }
+
+    /// Get the URL of the expression that generated this alert.
+    pub fn generator_url(&self) -> &str {
+        &self.generator_url
+    }
+
+    /// Get the names of the receivers this alert is routed to.
+    pub fn receivers(&self) -> Vec<&str> {
+        self.receivers.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    /// Get the status of this alert.
+    pub fn status(&self) -> &AlertStatus {
+        &self.status
+    }
+}
+
+/// A group of alerts as routed to a receiver.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AlertGroup {
+    pub(crate) labels: HashMap<String, String>,
+    receiver: Receiver,
+    pub(crate) alerts: Vec<Alert>,
+}
+
+impl AlertGroup {
+    /// Get the labels this group is grouped by.
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// Get the name of the receiver of this group.
+    pub fn receiver(&self) -> &str {
+        &self.receiver.name
+    }
+
+    /// Get the alerts of this group.
+    pub fn alerts(&self) -> &[Alert] {
+        &self.alerts
+    }
+}
+
+/// Possible states of a silence.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+pub enum SilenceState {
+    #[serde(alias = "expired")]
+    Expired,
+    #[serde(alias = "active")]
+    Active,
+    #[serde(alias = "pending")]
+    Pending,
+}
+
+impl fmt::Display for SilenceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SilenceState::Expired => write!(f, "expired"),
+            SilenceState::Active => write!(f, "active"),
+            SilenceState::Pending => write!(f, "pending"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SilenceStatus {
+    state: SilenceState,
+}
+
+/// A silence as stored by the Alertmanager.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Silence {
+    pub(crate) id: String,
+    status: SilenceStatus,
+    #[serde(alias = "updatedAt")]
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub(crate) updated_at: OffsetDateTime,
+    pub(crate) matchers: Vec<Matcher>,
+    #[serde(alias = "startsAt")]
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub(crate) starts_at: OffsetDateTime,
+    #[serde(alias = "endsAt")]
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub(crate) ends_at: OffsetDateTime,
+    #[serde(alias = "createdBy")]
+    pub(crate) created_by: String,
+    pub(crate) comment: String,
+}
+
+impl Silence {
+    /// Get the ID of this silence.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the state of this silence.
+    pub fn state(&self) -> SilenceState {
+        self.status.state
+    }
+
+    /// Get the time when this silence was last updated.
+    pub fn updated_at(&self) -> &OffsetDateTime {
+        &self.updated_at
+    }
+
+    /// Get the matchers of this silence.
+    pub fn matchers(&self) -> &[Matcher] {
+        &self.matchers
+    }
+
+    /// Get the time when this silence starts.
+    pub fn starts_at(&self) -> &OffsetDateTime {
+        &self.starts_at
+    }
+
+    /// Get the time when this silence ends.
+    pub fn ends_at(&self) -> &OffsetDateTime {
+        &self.ends_at
+    }
+
+    /// Get the author of this silence.
+    pub fn created_by(&self) -> &str {
+        &self.created_by
+    }
+
+    /// Get the comment of this silence.
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    /// Create a [NewSilence] from this silence in order to update it, e.g. to extend it
+    /// (see [Alertmanager::create_silence]).
+    pub fn to_update(&self) -> NewSilence {
+        NewSilence {
+            id: Some(self.id.clone()),
+            matchers: self.matchers.clone(),
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            created_by: self.created_by.clone(),
+            comment: self.comment.clone(),
+        }
+    }
+}
+
+/// A silence that is created or updated via [Alertmanager::create_silence].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewSilence {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) id: Option<String>,
+    pub(crate) matchers: Vec<Matcher>,
+    #[serde(rename = "startsAt")]
+    #[serde(serialize_with = "serialize_rfc3339")]
+    pub(crate) starts_at: OffsetDateTime,
+    #[serde(rename = "endsAt")]
+    #[serde(serialize_with = "serialize_rfc3339")]
+    pub(crate) ends_at: OffsetDateTime,
+    #[serde(rename = "createdBy")]
+    pub(crate) created_by: String,
+    pub(crate) comment: String,
+}
+
+impl NewSilence {
+    /// Create a new silence that starts now and lasts for the given duration.
+    pub fn new(duration: std::time::Duration, created_by: &str, comment: &str) -> Self {
+        let now = OffsetDateTime::now_utc();
+        NewSilence {
+            id: None,
+            matchers: vec![],
+            starts_at: now,
+            ends_at: now + duration,
+            created_by: created_by.to_string(),
+            comment: comment.to_string(),
+        }
+    }
+
+    /// Add a matcher to this silence.
+    pub fn matcher(mut self, matcher: Matcher) -> Self {
+        self.matchers.push(matcher);
+        self
+    }
+
+    /// Add multiple matchers to this silence, e.g. those created by [Matcher::from_labels].
+    pub fn matchers(mut self, matchers: impl IntoIterator<Item = Matcher>) -> Self {
+        self.matchers.extend(matchers);
+        self
+    }
+
+    /// Set the time when this silence starts.
+    pub fn starts_at(mut self, starts_at: OffsetDateTime) -> Self {
+        self.starts_at = starts_at;
+        self
+    }
+
+    /// Set the time when this silence ends.
+    pub fn ends_at(mut self, ends_at: OffsetDateTime) -> Self {
+        self.ends_at = ends_at;
+        self
+    }
+}
+
+/// A peer of an Alertmanager cluster.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Peer {
+    pub(crate) name: String,
+    pub(crate) address: String,
+}
+
+impl Peer {
+    /// Get the name of this peer.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the address of this peer.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+/// The status of an Alertmanager cluster.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ClusterStatus {
+    pub(crate) name: Option<String>,
+    pub(crate) status: String,
+    #[serde(default)]
+    pub(crate) peers: Vec<Peer>,
+}
+
+impl ClusterStatus {
+    /// Get the name of this Alertmanager within the cluster.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the status of the cluster, i.e. "ready", "settling" or "disabled".
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    /// Get the peers of the cluster.
+    pub fn peers(&self) -> &[Peer] {
+        &self.peers
+    }
+}
+
+/// Build information of an Alertmanager.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VersionInfo {
+    pub(crate) version: String,
+    pub(crate) revision: String,
+    pub(crate) branch: String,
+    #[serde(alias = "buildUser")]
+    pub(crate) build_user: String,
+    #[serde(alias = "buildDate")]
+    pub(crate) build_date: String,
+    #[serde(alias = "goVersion")]
+    pub(crate) go_version: String,
+}
+
+impl VersionInfo {
+    /// Get the version.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Get the Git revision.
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    /// Get the Git branch.
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Get the user that built the binary.
+    pub fn build_user(&self) -> &str {
+        &self.build_user
+    }
+
+    /// Get the date the binary was built.
+    pub fn build_date(&self) -> &str {
+        &self.build_date
+    }
+
+    /// Get the Go version the binary was built with.
+    pub fn go_version(&self) -> &str {
+        &self.go_version
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct AlertmanagerConfig {
+    original: String,
+}
+
+/// The status of an Alertmanager.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AlertmanagerStatus {
+    pub(crate) cluster: ClusterStatus,
+    #[serde(alias = "versionInfo")]
+    pub(crate) version_info: VersionInfo,
+    config: AlertmanagerConfig,
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub(crate) uptime: OffsetDateTime,
+}
+
+impl AlertmanagerStatus {
+    /// Get the status of the cluster.
+    pub fn cluster(&self) -> &ClusterStatus {
+        &self.cluster
+    }
+
+    /// Get build information.
+    pub fn version_info(&self) -> &VersionInfo {
+        &self.version_info
+    }
+
+    /// Get the loaded configuration as YAML.
+    pub fn config(&self) -> &str {
+        &self.config.original
+    }
+
+    /// Get the time when the Alertmanager was started.
+    pub fn uptime(&self) -> &OffsetDateTime {
+        &self.uptime
+    }
+}
+
+#[derive(Deserialize)]
+struct SilenceId {
+    #[serde(alias = "silenceID")]
+    silence_id: String,
+}
+
+/// A client for the Alertmanager API v2.
+#[derive(Debug, Clone)]
+pub struct Alertmanager {
+    pub(crate) client: reqwest::Client,
+    pub(crate) base_url: String,
+}
+
+impl std::convert::TryFrom<&str> for Alertmanager {
+    type Error = crate::error::Error;
+
+    /// Create an Alertmanager client from the URL of an Alertmanager (see [Alertmanager::from]).
+    ///
+    /// ```rust
+    /// use prometheus_http_query::alertmanager::Alertmanager;
+    /// use std::convert::TryFrom;
+    ///
+    /// let alertmanager = Alertmanager::try_from("http://127.0.0.1:9093");
+    /// assert!(alertmanager.is_ok());
+    /// ```
+    fn try_from(url: &str) -> Result<Self, Self::Error> {
+        Alertmanager::from(reqwest::Client::new(), url)
+    }
+}
+
+impl Alertmanager {
+    /// Create an Alertmanager client from a custom [reqwest::Client] and the URL of an
+    /// Alertmanager. The URL may also be one of the alert endpoints returned by
+    /// [crate::Client::alertmanagers], e.g. `http://127.0.0.1:9093/api/v2/alerts`.
+    pub fn from(client: reqwest::Client, url: &str) -> Result<Self, Error> {
+        let url = url::Url::parse(url).map_err(Error::UrlParse)?;
+        let url = url.as_str().trim_end_matches('/');
+        let root = ["/api/v2/alerts", "/api/v1/alerts", "/api/v2"]
+            .iter()
+            .find_map(|suffix| url.strip_suffix(suffix))
+            .unwrap_or(url);
+        Ok(Alertmanager {
+            client,
+            base_url: format!("{}/api/v2", root),
+        })
+    }
+
+    /// Get the URL of the Alertmanager API v2.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Retrieve all alerts that match all of the given matchers, optionally only those
+    /// routed to a particular receiver (given as regular expression).
+    pub async fn alerts(
+        &self,
+        filter: &[Matcher],
+        receiver: Option<&str>,
+    ) -> Result<Vec<Alert>, Error> {
+        self.get("alerts", &filter_params(filter, receiver)).await
+    }
+
+    /// Retrieve all alert groups, see [Alertmanager::alerts] for the parameters.
+    pub async fn alert_groups(
+        &self,
+        filter: &[Matcher],
+        receiver: Option<&str>,
+    ) -> Result<Vec<AlertGroup>, Error> {
+        self.get("alerts/groups", &filter_params(filter, receiver))
+            .await
+    }
+
+    /// Retrieve all silences that match all of the given matchers.
+    pub async fn silences(&self, filter: &[Matcher]) -> Result<Vec<Silence>, Error> {
+        self.get("silences", &filter_params(filter, None)).await
+    }
+
+    /// Retrieve a single silence by its ID.
+    pub async fn silence(&self, id: &str) -> Result<Silence, Error> {
+        self.get(&format!("silence/{}", id), &[]).await
+    }
+
+    /// Create a new silence or update an existing one (see [Silence::to_update]) and
+    /// return its ID. Note that the Alertmanager replaces a silence with a new one if
+    /// its matchers change or it has already expired.
+    pub async fn create_silence(&self, silence: &NewSilence) -> Result<String, Error> {
+        let url = format!("{}/silences", self.base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(silence)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        let response: SilenceId = check_alertmanager_response(response)
+            .await?
+            .json()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        Ok(response.silence_id)
+    }
+
+    /// Silence an alert as returned by [crate::Client::alerts] for the given duration,
+    /// starting now, and return the ID of the new silence. The silence matches all labels
+    /// of the alert exactly.
+    pub async fn silence_alert(
+        &self,
+        alert: &crate::response::Alert,
+        duration: std::time::Duration,
+        created_by: &str,
+        comment: &str,
+    ) -> Result<String, Error> {
+        let silence = NewSilence::new(duration, created_by, comment)
+            .matchers(Matcher::from_labels(alert.labels()));
+        self.create_silence(&silence).await
+    }
+
+    /// Expire a silence immediately.
+    pub async fn expire_silence(&self, id: &str) -> Result<(), Error> {
+        let url = format!("{}/silence/{}", self.base_url, id);
+
+        let response = self
+            .client
+            .delete(&url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        check_alertmanager_response(response).await.map(|_| ())
+    }
+
+    /// Retrieve the status of the Alertmanager.
+    pub async fn status(&self) -> Result<AlertmanagerStatus, Error> {
+        self.get("status", &[]).await
+    }
+
+    /// Retrieve the names of all receivers.
+    pub async fn receivers(&self) -> Result<Vec<String>, Error> {
+        let receivers: Vec<Receiver> = self.get("receivers", &[]).await?;
+        Ok(receivers.into_iter().map(|r| r.name).collect())
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<T, Error> {
+        let url = format!("{}/{}", self.base_url, path);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(params)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        check_alertmanager_response(response)
+            .await?
+            .json()
+            .await
+            .map_err(Error::Reqwest)
+    }
+}
+
+fn filter_params(filter: &[Matcher], receiver: Option<&str>) -> Vec<(&'static str, String)> {
+    let mut params: Vec<(&str, String)> =
+        filter.iter().map(|m| ("filter", m.to_string())).collect();
+
+    if let Some(r) = receiver {
+        params.push(("receiver", r.to_string()));
+    }
+
+    params
+}
+
+// The Alertmanager responds with a JSON string (or plain text) that describes the error.
+async fn check_alertmanager_response(
+    response: reqwest::Response,
+) -> Result<reqwest::Response, Error> {
+    let status = response.status();
+
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.map_err(Error::Reqwest)?;
+    let message = serde_json::from_str::<String>(&body).unwrap_or(body);
+
+    Err(Error::AlertmanagerError(AlertmanagerError {
+        status: status.as_u16(),
+        message: message.trim().to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_base_url() {
+        let urls = [
+            "http://127.0.0.1:9093",
+            "http://127.0.0.1:9093/",
+            "http://127.0.0.1:9093/api/v2/alerts",
+            "http://127.0.0.1:9093/api/v1/alerts",
+        ];
+
+        for url in urls {
+            let alertmanager = Alertmanager::try_from(url).unwrap();
+            assert_eq!(alertmanager.base_url(), "http://127.0.0.1:9093/api/v2");
+        }
+    }
+
+    #[test]
+    fn test_matcher_display() {
+        assert_eq!(Matcher::equal("job", "node").to_string(), "job=\"node\"");
+        assert_eq!(
+            Matcher::not_equal("env", "test").to_string(),
+            "env!=\"test\""
+        );
+        assert_eq!(
+            Matcher::regex_match("instance", "db-.*").to_string(),
+            "instance=~\"db-.*\""
+        );
+        assert_eq!(
+            Matcher::no_regex_match("msg", "a\"b").to_string(),
+            "msg!~\"a\\\"b\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_alerts() {
+        let body = r#"[
+  {
+    "annotations": { "summary": "Instance down" },
+    "endsAt": "2021-06-10T18:30:00.000Z",
+    "fingerprint": "c2f4e2a9b0d2a4b1",
+    "receivers": [ { "name": "ops" } ],
+    "startsAt": "2021-06-10T17:26:00.000Z",
+    "status": { "inhibitedBy": [], "silencedBy": [ "3a1d5c2e" ], "state": "suppressed" },
+    "updatedAt": "2021-06-10T17:27:00.000Z",
+    "generatorURL": "http://prometheus:9090/graph",
+    "labels": { "alertname": "InstanceDown", "job": "node" }
+  }
+]"#;
+        let (url, received) = test_server::start(200, body).await;
+
+        let alertmanager = Alertmanager::try_from(url.as_str()).unwrap();
+
+        let alerts = alertmanager
+            .alerts(&[Matcher::equal("job", "node")], Some("ops"))
+            .await
+            .unwrap();
+
+        assert_eq!(alerts[0].fingerprint(), "c2f4e2a9b0d2a4b1");
+        assert_eq!(alerts[0].receivers(), vec!["ops"]);
+        assert_eq!(
+            alerts[0].status().state(),
+            AlertmanagerAlertState::Suppressed
+        );
+        assert_eq!(alerts[0].status().silenced_by(), &["3a1d5c2e"]);
+
+        assert_eq!(
+            received.lock().unwrap()[0].to_string(),
+            "GET /api/v2/alerts?filter=job%3D%22node%22&receiver=ops HTTP/1.1\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_silence() {
+        let (url, received) = test_server::start(200, r#"{"silenceID":"3a1d5c2e"}"#).await;
+
+        let alertmanager = Alertmanager::try_from(url.as_str()).unwrap();
+
+        let silence = NewSilence::new(std::time::Duration::from_secs(3600), "ops", "maintenance")
+            .matcher(Matcher::equal("alertname", "InstanceDown"))
+            .starts_at(time::macros::datetime!(2021-06-10 17:00 UTC))
+            .ends_at(time::macros::datetime!(2021-06-10 18:00 UTC));
+
+        let id = alertmanager.create_silence(&silence).await.unwrap();
+
+        assert_eq!(id, "3a1d5c2e");
+
+        assert_eq!(
+            received.lock().unwrap()[0].to_string(),
+            "POST /api/v2/silences HTTP/1.1\n\
+             {\"matchers\":[{\"name\":\"alertname\",\"value\":\"InstanceDown\",\"isRegex\":false,\"isEqual\":true}],\
+             \"startsAt\":\"2021-06-10T17:00:00Z\",\"endsAt\":\"2021-06-10T18:00:00Z\",\
+             \"createdBy\":\"ops\",\"comment\":\"maintenance\"}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expire_silence_for_error() {
+        let (url, _) = test_server::start(404, r#""silence 3a1d5c2e not found""#).await;
+
+        let alertmanager = Alertmanager::try_from(url.as_str()).unwrap();
+
+        let result = alertmanager.expire_silence("3a1d5c2e").await;
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "the Alertmanager responded with status 404: silence 3a1d5c2e not found"
+        );
+    }
+}
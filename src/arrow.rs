@@ -0,0 +1,295 @@
+//! Convert query results into [Apache Arrow](https://arrow.apache.org) [RecordBatch]es and
+//! write them to Parquet files, e.g. to load them into notebooks or a lakehouse.
+//!
+//! This module requires the `arrow` feature.
+//!
+//! ```rust
+//! use prometheus_http_query::arrow::{to_record_batch, write_parquet, Layout};
+//! use prometheus_http_query::{Client, Error};
+//! use std::fs::File;
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let client = Client::default();
+//!
+//!     let result = client.query_range("up", 1623345960, 1623346260, Some("30s"), None).await?;
+//!
+//!     // One row per sample with the columns "job", "instance", "timestamp" and "value".
+//!     let batch = to_record_batch(&result, Layout::Long)?;
+//!
+//!     assert_eq!(batch.schema().field_with_name("value").is_ok(), true);
+//!
+//!     // One row per timestamp and one column per time series.
+//!     let file = File::create(std::env::temp_dir().join("up.parquet")).unwrap();
+//!
+//!     write_parquet(file, &result, Layout::Wide)?;
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::error::{Error, InvalidFunctionArgument};
+use crate::response::{self, FloatSeries, QueryResultType};
+use ::arrow::array::{ArrayRef, Float64Array, StringDictionaryBuilder, TimestampMillisecondArray};
+use ::arrow::datatypes::{DataType, Field, Int32Type, Schema, TimeUnit};
+use ::arrow::record_batch::RecordBatch;
+use ::parquet::arrow::ArrowWriter;
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::sync::Arc;
+
+const TIMESTAMP: &str = "timestamp";
+const VALUE: &str = "value";
+
+/// The layout of the [RecordBatch]es that query results are converted into.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Layout {
+    /// One row per sample with one dictionary-encoded column per label name, a `timestamp`
+    /// and a `value` column. Labels that a time series does not have are null.
+    Long,
+    /// One row per timestamp with a `timestamp` column and one column per time series that
+    /// is named after its labels, e.g. `up{instance="localhost:9090",job="prometheus"}`, and
+    /// carries them as field metadata. Missing samples are null.
+    Wide,
+}
+
+/// Convert an instant vector, range vector or scalar result into a [RecordBatch] of the
+/// given layout. Timestamps are stored in milliseconds and only float samples are converted.
+pub fn to_record_batch(result: &QueryResultType, layout: Layout) -> Result<RecordBatch, Error> {
+    let series = result.float_series().ok_or_else(|| {
+        Error::InvalidFunctionArgument(InvalidFunctionArgument {
+            message: String::from("results of type 'string' cannot be converted to Arrow"),
+        })
+    })?;
+
+    match layout {
+        Layout::Long => long(&series),
+        Layout::Wide => wide(&series, matches!(result, QueryResultType::Scalar(_))),
+    }
+}
+
+/// Convert a query result into a [RecordBatch] (see [to_record_batch]) and write it to
+/// a Parquet file.
+pub fn write_parquet<W: Write + Send>(
+    writer: W,
+    result: &QueryResultType,
+    layout: Layout,
+) -> Result<(), Error> {
+    let batch = to_record_batch(result, layout)?;
+
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None).map_err(boxed)?;
+    writer.write(&batch).map_err(boxed)?;
+    writer.close().map_err(boxed)?;
+
+    Ok(())
+}
+
+fn boxed<E: std::error::Error + Send + Sync + 'static>(error: E) -> Error {
+    Error::Arrow(Box::new(error))
+}
+
+fn timestamp_field() -> Field {
+    Field::new(
+        TIMESTAMP,
+        DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        false,
+    )
+}
+
+fn timestamp_column(timestamps: Vec<i64>) -> ArrayRef {
+    Arc::new(TimestampMillisecondArray::from(timestamps).with_timezone("UTC"))
+}
+
+fn long(series: &FloatSeries) -> Result<RecordBatch, Error> {
+    let names: BTreeSet<&str> = series
+        .iter()
+        .flat_map(|(metric, _)| metric.keys().map(|k| k.as_str()))
+        .collect();
+
+    if let Some(name) = names.iter().find(|n| **n == TIMESTAMP || **n == VALUE) {
+        return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+            message: format!(
+                "the label '{}' conflicts with a column of the long layout, use the wide layout or drop the label",
+                name
+            ),
+        }));
+    }
+
+    let mut labels: Vec<StringDictionaryBuilder<Int32Type>> = names
+        .iter()
+        .map(|_| StringDictionaryBuilder::new())
+        .collect();
+    let mut timestamps = vec![];
+    let mut values = vec![];
+
+    for (metric, samples) in series {
+        for sample in samples.iter() {
+            for (name, builder) in names.iter().zip(labels.iter_mut()) {
+                match metric.get(*name) {
+                    Some(value) => {
+                        builder.append(value).map_err(boxed)?;
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            timestamps.push(sample.timestamp_millis());
+            values.push(sample.value());
+        }
+    }
+
+    let mut fields: Vec<Field> = names
+        .iter()
+        .map(|name| {
+            let dictionary =
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+            Field::new(*name, dictionary, true)
+        })
+        .collect();
+    fields.push(timestamp_field());
+    fields.push(Field::new(VALUE, DataType::Float64, false));
+
+    let mut columns: Vec<ArrayRef> = labels
+        .iter_mut()
+        .map(|builder| Arc::new(builder.finish()) as ArrayRef)
+        .collect();
+    columns.push(timestamp_column(timestamps));
+    columns.push(Arc::new(Float64Array::from(values)));
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(boxed)
+}
+
+fn wide(series: &FloatSeries, scalar: bool) -> Result<RecordBatch, Error> {
+    let timestamps: Vec<i64> = series
+        .iter()
+        .flat_map(|(_, samples)| samples.iter().map(|s| s.timestamp_millis()))
+        .collect::<BTreeSet<i64>>()
+        .into_iter()
+        .collect();
+
+    let mut fields = vec![timestamp_field()];
+    let mut columns = vec![];
+
+    for (metric, samples) in series {
+        let mut values = vec![None; timestamps.len()];
+
+        for sample in samples.iter() {
+            if let Ok(i) = timestamps.binary_search(&sample.timestamp_millis()) {
+                values[i] = Some(sample.value());
+            }
+        }
+
+        let name = if scalar {
+            VALUE.to_string()
+        } else {
+            response::series_name(metric)
+        };
+
+        let field = Field::new(name, DataType::Float64, true)
+            .with_metadata(metric.iter().map(|(k, v)| (k.clone(), v.clone())).collect());
+
+        fields.push(field);
+        columns.push(Arc::new(Float64Array::from(values)) as ArrayRef);
+    }
+
+    columns.insert(0, timestamp_column(timestamps));
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(boxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::response::{InstantVector, Sample};
+    use ::arrow::array::{Array, DictionaryArray, StringArray};
+    use std::collections::HashMap;
+
+    fn matrix() -> QueryResultType {
+        QueryResultType::Matrix(fixtures::matrix())
+    }
+
+    #[test]
+    fn test_long_layout() {
+        let batch = to_record_batch(&matrix(), Layout::Long).unwrap();
+
+        let names: Vec<String> = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect();
+
+        assert_eq!(names, ["__name__", "instance", "job", "timestamp", "value"]);
+        assert_eq!(batch.num_rows(), 4);
+
+        let job = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        let dictionary = job.values().as_any().downcast_ref::<StringArray>().unwrap();
+
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.value(job.keys().value(3) as usize), "prometheus");
+        assert!(batch.column(1).is_null(3));
+
+        let timestamps = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+
+        assert_eq!(timestamps.value(0), 1435781430000);
+    }
+
+    #[test]
+    fn test_wide_layout() {
+        let batch = to_record_batch(&matrix(), Layout::Wide).unwrap();
+        let schema = batch.schema();
+
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(
+            schema.field(1).name(),
+            r#"up{instance="a:9100",job="node"}"#
+        );
+        assert_eq!(schema.field(2).name(), r#"up{job="prometheus"}"#);
+        assert_eq!(schema.field(2).metadata().get("job").unwrap(), "prometheus");
+        assert!(batch.column(2).is_null(0));
+
+        let vector = QueryResultType::Vector(vec![InstantVector::new(
+            HashMap::new(),
+            Sample::new(1435781451.781, 2.0),
+        )]);
+        let batch = to_record_batch(&vector, Layout::Wide).unwrap();
+
+        assert_eq!(batch.schema().field(1).name(), "{}");
+
+        let scalar = QueryResultType::Scalar(Sample::new(1435781451.781, 2.0));
+        let batch = to_record_batch(&scalar, Layout::Wide).unwrap();
+
+        assert_eq!(batch.schema().field(1).name(), "value");
+    }
+
+    #[test]
+    fn test_long_layout_for_error() {
+        let mut conflicting = fixtures::metric("node", None);
+        conflicting.insert("value".to_string(), "x".to_string());
+
+        let vector = QueryResultType::Vector(vec![InstantVector::new(
+            conflicting,
+            Sample::new(1435781451.781, 2.0),
+        )]);
+
+        assert!(to_record_batch(&vector, Layout::Long).is_err());
+        assert!(to_record_batch(&vector, Layout::Wide).is_ok());
+    }
+
+    #[test]
+    fn test_write_parquet() {
+        let mut file = vec![];
+
+        write_parquet(&mut file, &matrix(), Layout::Long).unwrap();
+
+        assert!(file.starts_with(b"PAR1"));
+        assert!(file.ends_with(b"PAR1"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Export range query results as [OpenMetrics](crate::exposition) files that can be used
+//! to backfill data, e.g. to evaluate a new recording rule over historical data:
+//!
+//! ```text
+//! promtool tsdb create-blocks-from openmetrics backfill.om ./data
+//! ```
+//!
+//! ```rust
+//! use prometheus_http_query::backfill::Exporter;
+//! use prometheus_http_query::{Client, Error};
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let client = Client::default();
+//!
+//!     let q = "sum by (job) (rate(prometheus_http_requests_total[5m]))";
+//!
+//!     let result = client.query_range(q, 1623345960, 1623346260, Some("30s"), None).await?;
+//!
+//!     let exporter = Exporter::new()
+//!         .rename("job:prometheus_http_requests:rate5m")
+//!         .label("source", "backfill");
+//!
+//!     let file = exporter.export(&result)?;
+//!
+//!     assert!(file.ends_with("# EOF\n"));
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::error::{Error, InvalidFunctionArgument};
+use crate::exposition::{self, Cursor, MetricFamily, MetricSample};
+use crate::response::{MetricType, QueryResultType, RangeVector};
+use std::collections::{BTreeMap, HashSet};
+
+/// Converts the series of a range query result into an OpenMetrics file, keeping the
+/// exact timestamps of all samples.
+#[derive(Debug, Clone, Default)]
+pub struct Exporter {
+    pub(crate) metric: Option<String>,
+    pub(crate) help: Option<String>,
+    pub(crate) labels: BTreeMap<String, String>,
+}
+
+impl Exporter {
+    /// Create a new exporter that keeps metric names and labels as they are.
+    pub fn new() -> Self {
+        Exporter::default()
+    }
+
+    /// Set the metric name of all exported series. This is required for series without a
+    /// metric name, e.g. the result of an aggregation or a function like `rate()`.
+    pub fn rename(mut self, metric: &str) -> Self {
+        self.metric = Some(metric.to_string());
+        self
+    }
+
+    /// Set the help text of the exported metric families.
+    pub fn help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    /// Add a label to all exported series or override the value of an existing label.
+    /// An empty value removes the label.
+    pub fn label(mut self, name: &str, value: &str) -> Self {
+        self.labels.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Export the result of a range query (see [crate::Client::query_range]) as OpenMetrics
+    /// file, terminated by `# EOF`. Only results of type `matrix` can be exported.
+    ///
+    /// All series are exported as metrics of type `unknown`, native histogram samples are skipped.
+    pub fn export(&self, result: &QueryResultType) -> Result<String, Error> {
+        match result {
+            QueryResultType::Matrix(series) => self.export_series(series),
+            _ => Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                message: String::from(
+                    "only results of type 'matrix' can be exported for backfilling",
+                ),
+            })),
+        }
+    }
+
+    /// Export a set of series (see [Exporter::export]).
+    pub fn export_series(&self, series: &[RangeVector]) -> Result<String, Error> {
+        let invalid =
+            |message: String| Error::InvalidFunctionArgument(InvalidFunctionArgument { message });
+
+        let mut families: Vec<MetricFamily> = vec![];
+        let mut seen = HashSet::new();
+
+        for s in series {
+            let mut labels: BTreeMap<String, String> = s
+                .metric()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+
+            labels.remove("__name__");
+
+            for (name, value) in &self.labels {
+                match value.is_empty() {
+                    true => labels.remove(name),
+                    false => labels.insert(name.to_string(), value.to_string()),
+                };
+            }
+
+            let metric = self
+                .metric
+                .as_deref()
+                .or_else(|| s.metric().get("__name__").map(|m| m.as_str()))
+                .ok_or_else(|| {
+                    invalid(String::from(
+                        "a series without metric name cannot be exported, a new name must be provided",
+                    ))
+                })?;
+
+            let mut cursor = Cursor::new(metric);
+
+            if cursor.metric_name().is_none() || !cursor.rest().is_empty() {
+                return Err(invalid(format!("{} is not a valid metric name", metric)));
+            }
+
+            if !seen.insert((metric.to_string(), labels.clone())) {
+                return Err(invalid(format!(
+                    "the series {}{:?} is exported more than once",
+                    metric, labels
+                )));
+            }
+
+            let index = match families.iter().position(|f| f.name() == metric) {
+                Some(index) => index,
+                None => {
+                    let mut family = MetricFamily::new(metric, MetricType::Unknown);
+                    family.help = self.help.clone();
+                    families.push(family);
+                    families.len() - 1
+                }
+            };
+
+            for sample in s.samples() {
+                let mut point = MetricSample::new(metric, sample.value())
+                    .with_timestamp(sample.timestamp_millis());
+                point.labels = labels.clone();
+                families[index].samples.push(point);
+            }
+        }
+
+        Ok(exposition::encode_openmetrics(&families))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn matrix() -> QueryResultType {
+        QueryResultType::Matrix(fixtures::matrix())
+    }
+
+    #[test]
+    fn test_export() {
+        let result = Exporter::new()
+            .rename("up:backfilled")
+            .label("instance", "")
+            .label("source", "backfill")
+            .export(&matrix())
+            .unwrap();
+
+        assert_eq!(
+            result,
+            "# TYPE up:backfilled unknown\n\
+             up:backfilled{job=\"node\",source=\"backfill\"} 1 1435781430\n\
+             up:backfilled{job=\"node\",source=\"backfill\"} 0 1435781445\n\
+             up:backfilled{job=\"node\",source=\"backfill\"} 1 1435781475\n\
+             up:backfilled{job=\"prometheus\",source=\"backfill\"} 1 1435781445\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn test_export_for_error() {
+        let scalar =
+            QueryResultType::Scalar(serde_json::from_str("[1435781430.781, \"1\"]").unwrap());
+
+        assert!(Exporter::new().export(&scalar).is_err());
+        assert!(Exporter::new()
+            .rename("not a name")
+            .export(&matrix())
+            .is_err());
+        assert!(Exporter::new()
+            .label("job", "")
+            .label("instance", "")
+            .export(&matrix())
+            .is_err());
+    }
+}
@@ -1,13 +1,24 @@
+use crate::columnar::ColumnarMatrix;
 use crate::error::{
-    Error, InvalidFunctionArgument, ResponseError, UnknownResponseStatus,
+    ConfigReloadFailed, Error, InvalidFunctionArgument, ResponseError, UnknownResponseStatus,
     UnsupportedQueryResultType,
 };
+use crate::exposition::{self, MetricFamily};
 use crate::response::*;
 use crate::selector::Selector;
-use crate::util::{validate_duration, RuleType, TargetState};
+use crate::stream::SeriesStream;
+use crate::util::{validate_duration, RuleFilter, RuleType, TargetState};
+use crate::version::Capabilities;
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use url::Url;
 
+// The minimum interval between two readiness probes, so that a server that refuses
+// connections is not polled in a tight loop.
+const MIN_READINESS_INTERVAL: Duration = Duration::from_millis(100);
+
 /// A client used to execute queries. It uses a [reqwest::Client] internally
 /// that manages connections for us.
 ///
@@ -18,6 +29,7 @@ use url::Url;
 pub struct Client {
     pub(crate) client: reqwest::Client,
     pub(crate) base_url: String,
+    pub(crate) capabilities: Option<Capabilities>,
 }
 
 impl Default for Client {
@@ -32,6 +44,7 @@ impl Default for Client {
         Client {
             client: reqwest::Client::new(),
             base_url: String::from("http://127.0.0.1:9090/api/v1"),
+            capabilities: None,
         }
     }
 }
@@ -54,6 +67,7 @@ impl std::str::FromStr for Client {
         let client = Client {
             base_url: format!("{}/api/v1", url),
             client: reqwest::Client::new(),
+            capabilities: None,
         };
         Ok(client)
     }
@@ -77,6 +91,7 @@ impl std::convert::TryFrom<&str> for Client {
         let client = Client {
             base_url: format!("{}/api/v1", url),
             client: reqwest::Client::new(),
+            capabilities: None,
         };
         Ok(client)
     }
@@ -101,6 +116,7 @@ impl std::convert::TryFrom<String> for Client {
         let client = Client {
             base_url: format!("{}/api/v1", url),
             client: reqwest::Client::new(),
+            capabilities: None,
         };
         Ok(client)
     }
@@ -153,6 +169,20 @@ impl Client {
         &self.base_url
     }
 
+    // The root URL of the server, i.e. the base URL without "/api/v1".
+    fn root_url(&self) -> &str {
+        self.base_url
+            .strip_suffix("/api/v1")
+            .unwrap_or(&self.base_url)
+            .trim_end_matches('/')
+    }
+
+    // Build the URL of an endpoint of the management API, which is located
+    // at the root of the server instead of below "/api/v1".
+    fn management_url(&self, path: &str) -> String {
+        format!("{}/-/{}", self.root_url(), path)
+    }
+
     /// Create a Client from a custom [reqwest::Client] and URL.
     /// This way you can account for all extra parameters (e.g. x509 authentication)
     /// that may be needed to connect to Prometheus or an intermediate proxy,
@@ -176,7 +206,76 @@ impl Client {
     /// ```
     pub fn from(client: reqwest::Client, url: &str) -> Result<Self, Error> {
         let base_url = format!("{}/api/v1", Url::parse(url).map_err(Error::UrlParse)?);
-        Ok(Client { base_url, client })
+        Ok(Client {
+            base_url,
+            client,
+            capabilities: None,
+        })
+    }
+
+    /// Use the given [Capabilities] instead of detecting them (see [Client::detect_capabilities]),
+    /// e.g. when the version of the server is known in advance. This is also required to
+    /// enable the checks for servers that implement the Prometheus API but have their own
+    /// versioning, e.g. Thanos or Cortex.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Capabilities, Client, Version};
+    ///
+    /// let client = Client::default().with_capabilities(Capabilities::new(Version::new(2, 45, 0)));
+    ///
+    /// assert_eq!(client.capabilities().unwrap().version(), Version::new(2, 45, 0));
+    /// ```
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Get the [Capabilities] of the server, if they were detected or set before.
+    pub fn capabilities(&self) -> Option<&Capabilities> {
+        self.capabilities.as_ref()
+    }
+
+    /// Detect the version of the server using its build information and keep its [Capabilities].
+    /// From then on, expressions that use functions the server does not support fail with
+    /// [Error::UnsupportedFunction] before they are sent.
+    ///
+    /// Only versions that look like Prometheus versions (>= v2.0) are used. Other servers that
+    /// implement the Prometheus API, e.g. Thanos (which reports versions like `0.34.0`), have
+    /// their own versioning, so `None` is returned and no checks are enabled for them. Use
+    /// [Client::with_capabilities] to enable them anyway.
+    ///
+    /// The capabilities are only used to check expressions. Responses do not depend on them,
+    /// as response fields that are not returned by all supported versions are modeled as
+    /// [Option]s.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let mut client = Client::default();
+    ///
+    ///     if let Some(capabilities) = client.detect_capabilities().await? {
+    ///         if !capabilities.supports_function("present_over_time") {
+    ///             println!("server version {} is too old", capabilities.version());
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn detect_capabilities(&mut self) -> Result<Option<&Capabilities>, Error> {
+        let build = self.build_information().await?;
+        self.capabilities = Capabilities::from_build_version(build.version());
+        Ok(self.capabilities.as_ref())
+    }
+
+    // Check an expression against the capabilities of the server, if they are known.
+    fn check_capabilities(&self, query: &str) -> Result<(), Error> {
+        match &self.capabilities {
+            Some(capabilities) => capabilities.check(query),
+            None => Ok(()),
+        }
     }
 
     /// Perform an instant query using a [crate::RangeVector] or [crate::InstantVector].
@@ -209,9 +308,96 @@ impl Client {
         time: Option<i64>,
         timeout: Option<&str>,
     ) -> Result<QueryResultType, Error> {
+        self.send_query(vector, time, timeout, false)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Perform an instant query just like [Client::query], but additionally request
+    /// statistics (timings and sample counts) of the query evaluation.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, InstantVector, Selector, Error};
+    /// use std::convert::TryInto;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let v: InstantVector = Selector::new()
+    ///         .metric("node_cpu_seconds_total")
+    ///         .try_into()?;
+    ///
+    ///     let (response, stats) = client.query_with_stats(v, None, None).await?;
+    ///
+    ///     assert!(response.as_instant().is_some());
+    ///     assert!(stats.timings().exec_total_time() > 0.0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_with_stats(
+        &self,
+        vector: impl std::fmt::Display,
+        time: Option<i64>,
+        timeout: Option<&str>,
+    ) -> Result<(QueryResultType, QueryStats), Error> {
+        let (result, stats) = self.send_query(vector, time, timeout, true).await?;
+        Ok((result, stats.ok_or(Error::MissingField)?))
+    }
+
+    /// Perform an instant query just like [Client::query], but decode the resulting
+    /// [crate::response::InstantVector]s one at a time while the response is received
+    /// (see [crate::stream]).
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let mut series = client.query_stream("up", None, None).await?;
+    ///
+    ///     while let Some(instant_vector) = series.next().await {
+    ///         assert!(instant_vector?.metric().contains_key("job"));
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_stream(
+        &self,
+        vector: impl std::fmt::Display,
+        time: Option<i64>,
+        timeout: Option<&str>,
+    ) -> Result<SeriesStream<InstantVector>, Error> {
+        let response = self.request_query(vector, time, timeout, false).await?;
+        Ok(SeriesStream::new(response, "vector"))
+    }
+
+    async fn send_query(
+        &self,
+        vector: impl std::fmt::Display,
+        time: Option<i64>,
+        timeout: Option<&str>,
+        stats: bool,
+    ) -> Result<(QueryResultType, Option<QueryStats>), Error> {
+        let response = self.request_query(vector, time, timeout, stats).await?;
+        parse_query_response(response).await
+    }
+
+    async fn request_query(
+        &self,
+        vector: impl std::fmt::Display,
+        time: Option<i64>,
+        timeout: Option<&str>,
+        stats: bool,
+    ) -> Result<reqwest::Response, Error> {
         let url = format!("{}/query", self.base_url);
 
         let query = vector.to_string();
+        self.check_capabilities(&query)?;
         let mut params = vec![("query", query.as_str())];
 
         let time = time.map(|t| t.to_string());
@@ -225,19 +411,18 @@ impl Client {
             params.push(("timeout", t));
         }
 
-        let response = self
-            .client
+        if stats {
+            params.push(("stats", "all"));
+        }
+
+        self.client
             .get(&url)
             .query(params.as_slice())
             .send()
             .await
             .map_err(Error::Reqwest)?
             .error_for_status()
-            .map_err(Error::Reqwest)?;
-
-        check_response(response)
-            .await
-            .and_then(convert_query_response)
+            .map_err(Error::Reqwest)
     }
 
     pub async fn query_range(
@@ -248,9 +433,171 @@ impl Client {
         step: Option<&str>,
         timeout: Option<&str>,
     ) -> Result<QueryResultType, Error> {
+        self.send_query_range(vector, start, end, step, timeout, false)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Perform a range query just like [Client::query_range], but additionally request
+    /// statistics (timings and sample counts, including samples per step) of the query evaluation.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, InstantVector, Selector, Error};
+    /// use std::convert::TryInto;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let v: InstantVector = Selector::new()
+    ///         .metric("up")
+    ///         .try_into()?;
+    ///
+    ///     let (response, stats) = client
+    ///         .query_range_with_stats(v, 1623345960, 1623841309, Some("5m"), None)
+    ///         .await?;
+    ///
+    ///     assert!(response.as_range().is_some());
+    ///     assert!(!stats.samples().total_queryable_samples_per_step().is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_range_with_stats(
+        &self,
+        vector: impl std::fmt::Display,
+        start: i64,
+        end: i64,
+        step: Option<&str>,
+        timeout: Option<&str>,
+    ) -> Result<(QueryResultType, QueryStats), Error> {
+        let (result, stats) = self
+            .send_query_range(vector, start, end, step, timeout, true)
+            .await?;
+        Ok((result, stats.ok_or(Error::MissingField)?))
+    }
+
+    /// Perform a range query just like [Client::query_range], but decode the resulting
+    /// [crate::response::RangeVector]s one at a time while the response is received, so
+    /// that huge matrices need not be held in memory (see [crate::stream]).
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    /// use futures_util::TryStreamExt;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let series = client
+    ///         .query_range_stream("up", 1623345960, 1623841309, Some("5m"), None)
+    ///         .await?;
+    ///
+    ///     let samples = series
+    ///         .into_stream()
+    ///         .try_fold(0, |total, range_vector| async move {
+    ///             Ok(total + range_vector.samples().len())
+    ///         })
+    ///         .await?;
+    ///
+    ///     assert!(samples > 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_range_stream(
+        &self,
+        vector: impl std::fmt::Display,
+        start: i64,
+        end: i64,
+        step: Option<&str>,
+        timeout: Option<&str>,
+    ) -> Result<SeriesStream<RangeVector>, Error> {
+        let response = self
+            .request_query_range(vector, start, end, step, timeout, false)
+            .await?;
+        Ok(SeriesStream::new(response, "matrix"))
+    }
+
+    /// Perform a range query just like [Client::query_range], but decode the result into
+    /// a [ColumnarMatrix] one series at a time, so that the much larger row-oriented form
+    /// of the result is never held in memory (see [crate::columnar]). The warnings of the
+    /// response, e.g. about a truncated result, are returned along with the matrix.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let matrix = client
+    ///         .query_range_columnar("up", 1623345960, 1623841309, Some("5m"), None)
+    ///         .await?;
+    ///
+    ///     for series in matrix.series() {
+    ///         println!("{:?}: {:?}", series.labels().get("job"), series.values());
+    ///     }
+    ///
+    ///     for warning in matrix.warnings() {
+    ///         eprintln!("{}", warning);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_range_columnar(
+        &self,
+        vector: impl std::fmt::Display,
+        start: i64,
+        end: i64,
+        step: Option<&str>,
+        timeout: Option<&str>,
+    ) -> Result<Limited<ColumnarMatrix>, Error> {
+        let mut series = self
+            .query_range_stream(vector, start, end, step, timeout)
+            .await?;
+
+        let mut matrix = ColumnarMatrix::new();
+
+        while let Some(range_vector) = series.next().await {
+            matrix.push(&range_vector?);
+        }
+
+        Ok(Limited {
+            data: matrix,
+            warnings: series.warnings().to_vec(),
+        })
+    }
+
+    async fn send_query_range(
+        &self,
+        vector: impl std::fmt::Display,
+        start: i64,
+        end: i64,
+        step: Option<&str>,
+        timeout: Option<&str>,
+        stats: bool,
+    ) -> Result<(QueryResultType, Option<QueryStats>), Error> {
+        let response = self
+            .request_query_range(vector, start, end, step, timeout, stats)
+            .await?;
+        parse_query_response(response).await
+    }
+
+    async fn request_query_range(
+        &self,
+        vector: impl std::fmt::Display,
+        start: i64,
+        end: i64,
+        step: Option<&str>,
+        timeout: Option<&str>,
+        stats: bool,
+    ) -> Result<reqwest::Response, Error> {
         let url = format!("{}/query_range", self.base_url);
 
         let query = vector.to_string();
+        self.check_capabilities(&query)?;
         let start = start.to_string();
         let end = end.to_string();
 
@@ -269,19 +616,18 @@ impl Client {
             params.push(("timeout", t));
         }
 
-        let response = self
-            .client
+        if stats {
+            params.push(("stats", "all"));
+        }
+
+        self.client
             .get(&url)
             .query(params.as_slice())
             .send()
             .await
             .map_err(Error::Reqwest)?
             .error_for_status()
-            .map_err(Error::Reqwest)?;
-
-        check_response(response)
-            .await
-            .and_then(convert_query_response)
+            .map_err(Error::Reqwest)
     }
 
     /// Find time series that match certain label sets ([Selector]s).
@@ -302,10 +648,17 @@ impl Client {
     ///
     ///     let set = vec![s1, s2];
     ///
-    ///     let response = client.series(&set, None, None).await;
+    ///     let response = client.series(&set, None, None, None).await;
     ///
     ///     assert!(response.is_ok());
     ///
+    ///     // Return at most 100 series and check whether there are more:
+    ///     let response = client.series(&set, None, None, Some(100)).await?;
+    ///
+    ///     if response.is_truncated() {
+    ///         println!("showing only the first {} series", response.len());
+    ///     }
+    ///
     ///     Ok(())
     /// }
     /// ```
@@ -314,7 +667,8 @@ impl Client {
         selectors: &[Selector<'_>],
         start: Option<i64>,
         end: Option<i64>,
-    ) -> Result<Vec<HashMap<String, String>>, Error> {
+        limit: Option<usize>,
+    ) -> Result<Limited<Vec<HashMap<String, String>>>, Error> {
         let url = format!("{}/series", self.base_url);
 
         let mut params = vec![];
@@ -331,23 +685,19 @@ impl Client {
             params.push(("end", e.as_str()));
         }
 
+        let limit = limit.map(|l| l.to_string());
+
+        if let Some(l) = &limit {
+            params.push(("limit", l.as_str()));
+        }
+
         if selectors.is_empty() {
             return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
                 message: String::from("at least one match[] argument (Selector) must be provided in order to query the series endpoint")
             }));
         }
 
-        let selectors: Vec<String> = selectors
-            .iter()
-            .map(|s| match s.to_string().as_str().split_once('}') {
-                Some(split) => {
-                    let mut s = split.0.to_owned();
-                    s.push('}');
-                    s
-                }
-                None => s.to_string(),
-            })
-            .collect();
+        let selectors = match_params(selectors);
 
         for selector in &selectors {
             params.push(("match[]", &selector));
@@ -367,7 +717,10 @@ impl Client {
             let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
             let result: Vec<HashMap<String, String>> =
                 serde_json::from_value(data).map_err(Error::ResponseParse)?;
-            Ok(result)
+            Ok(Limited {
+                data: result,
+                warnings: warnings(&r),
+            })
         })
     }
 
@@ -381,7 +734,7 @@ impl Client {
     ///     let client = Client::default();
     ///
     ///     // To retrieve a list of all labels:
-    ///     let response = client.label_names(None, None, None).await;
+    ///     let response = client.label_names(None, None, None, None).await;
     ///
     ///     assert!(response.is_ok());
     ///
@@ -395,7 +748,7 @@ impl Client {
     ///
     ///     let set = Some(vec![s1, s2]);
     ///
-    ///     let response = client.label_names(set, None, None).await;
+    ///     let response = client.label_names(set, None, None, None).await;
     ///
     ///     assert!(response.is_ok());
     ///
@@ -407,7 +760,8 @@ impl Client {
         selectors: Option<Vec<Selector<'_>>>,
         start: Option<i64>,
         end: Option<i64>,
-    ) -> Result<Vec<String>, Error> {
+        limit: Option<usize>,
+    ) -> Result<Limited<Vec<String>>, Error> {
         let url = format!("{}/labels", self.base_url);
 
         let mut params = vec![];
@@ -424,18 +778,13 @@ impl Client {
             params.push(("end", e.as_str()));
         }
 
-        let selectors: Option<Vec<String>> = selectors.map(|vec| {
-            vec.iter()
-                .map(|s| match s.to_string().as_str().split_once('}') {
-                    Some(split) => {
-                        let mut s = split.0.to_owned();
-                        s.push('}');
-                        s
-                    }
-                    None => s.to_string(),
-                })
-                .collect()
-        });
+        let limit = limit.map(|l| l.to_string());
+
+        if let Some(l) = &limit {
+            params.push(("limit", l.as_str()));
+        }
+
+        let selectors = selectors.map(|s| match_params(&s));
 
         if let Some(ref selector_vec) = selectors {
             for selector in selector_vec {
@@ -456,7 +805,10 @@ impl Client {
         check_response(response).await.and_then(move |r| {
             let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
             let result: Vec<String> = serde_json::from_value(data).map_err(Error::ResponseParse)?;
-            Ok(result)
+            Ok(Limited {
+                data: result,
+                warnings: warnings(&r),
+            })
         })
     }
 
@@ -470,7 +822,7 @@ impl Client {
     ///     let client = Client::default();
     ///
     ///     // To retrieve a list of all label values for a specific label name:
-    ///     let response = client.label_values("job", None, None, None).await;
+    ///     let response = client.label_values("job", None, None, None, None).await;
     ///
     ///     assert!(response.is_ok());
     ///
@@ -480,7 +832,7 @@ impl Client {
     ///
     ///     let set = Some(vec![s1]);
     ///
-    ///     let response = client.label_values("job", set, None, None).await;
+    ///     let response = client.label_values("job", set, None, None, Some(10)).await;
     ///
     ///     assert!(response.is_ok());
     ///
@@ -493,7 +845,8 @@ impl Client {
         selectors: Option<Vec<Selector<'_>>>,
         start: Option<i64>,
         end: Option<i64>,
-    ) -> Result<Vec<String>, Error> {
+        limit: Option<usize>,
+    ) -> Result<Limited<Vec<String>>, Error> {
         let url = format!("{}/label/{}/values", self.base_url, label);
 
         let mut params = vec![];
@@ -510,18 +863,13 @@ impl Client {
             params.push(("end", e.as_str()));
         }
 
-        let selectors: Option<Vec<String>> = selectors.map(|vec| {
-            vec.iter()
-                .map(|s| match s.to_string().as_str().split_once('}') {
-                    Some(split) => {
-                        let mut s = split.0.to_owned();
-                        s.push('}');
-                        s
-                    }
-                    None => s.to_string(),
-                })
-                .collect()
-        });
+        let limit = limit.map(|l| l.to_string());
+
+        if let Some(l) = &limit {
+            params.push(("limit", l.as_str()));
+        }
+
+        let selectors = selectors.map(|s| match_params(&s));
 
         if let Some(ref selector_vec) = selectors {
             for selector in selector_vec {
@@ -542,42 +890,56 @@ impl Client {
         check_response(response).await.and_then(move |r| {
             let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
             let result = serde_json::from_value(data).map_err(Error::ResponseParse)?;
-            Ok(result)
+            Ok(Limited {
+                data: result,
+                warnings: warnings(&r),
+            })
         })
     }
 
-    /// Query the current state of target discovery.
+    /// Retrieve exemplars for time series that match a PromQL expression within a specific time range.
     ///
     /// ```rust
-    /// use prometheus_http_query::{Client, Error, TargetState};
+    /// use prometheus_http_query::{Client, Error, Selector, InstantVector};
     /// use std::convert::TryInto;
     ///
     /// #[tokio::main(flavor = "current_thread")]
     /// async fn main() -> Result<(), Error> {
     ///     let client = Client::default();
     ///
-    ///     let response = client.targets(None).await;
-    ///
-    ///     assert!(response.is_ok());
+    ///     let v: InstantVector = Selector::new()
+    ///         .metric("prometheus_http_request_duration_seconds_bucket")
+    ///         .try_into()?;
     ///
-    ///     // Filter targets by type:
-    ///     let response = client.targets(Some(TargetState::Active)).await;
+    ///     let response = client.query_exemplars(v, 1623345960, 1623841309).await?;
     ///
-    ///     assert!(response.is_ok());
+    ///     for collection in &response {
+    ///         for trace_id in collection.trace_ids() {
+    ///             println!("{}", trace_id);
+    ///         }
+    ///     }
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn targets(&self, state: Option<TargetState>) -> Result<Targets, Error> {
-        let url = format!("{}/targets", self.base_url);
-
-        let mut params = vec![];
+    pub async fn query_exemplars(
+        &self,
+        vector: impl std::fmt::Display,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<ExemplarCollection>, Error> {
+        let url = format!("{}/query_exemplars", self.base_url);
 
-        let state = state.map(|s| s.to_string());
+        let query = vector.to_string();
+        self.check_capabilities(&query)?;
+        let start = start.to_string();
+        let end = end.to_string();
 
-        if let Some(s) = &state {
-            params.push(("state", s.as_str()))
-        }
+        let params = vec![
+            ("query", query.as_str()),
+            ("start", start.as_str()),
+            ("end", end.as_str()),
+        ];
 
         let response = self
             .client
@@ -591,152 +953,880 @@ impl Client {
 
         check_response(response).await.and_then(move |r| {
             let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
-            let targets: Targets = serde_json::from_value(data).map_err(Error::ResponseParse)?;
-            Ok(targets)
+            let result: Vec<ExemplarCollection> =
+                serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(result)
+        })
+    }
+
+    /// Query the current state of target discovery.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error, TargetState};
+    /// use std::convert::TryInto;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.targets(None, None).await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     // Filter targets by type:
+    ///     let response = client.targets(Some(TargetState::Active), None).await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     // Only return the targets of a single scrape pool (usually the job name):
+    ///     let response = client.targets(None, Some("prometheus")).await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn targets(
+        &self,
+        state: Option<TargetState>,
+        scrape_pool: Option<&str>,
+    ) -> Result<Targets, Error> {
+        let url = format!("{}/targets", self.base_url);
+
+        let mut params = vec![];
+
+        let state = state.map(|s| s.to_string());
+
+        if let Some(s) = &state {
+            params.push(("state", s.as_str()))
+        }
+
+        if let Some(p) = scrape_pool {
+            params.push(("scrapePool", p))
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .query(params.as_slice())
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
+            let targets: Targets = serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(targets)
         })
     }
 
     /// Retrieve a list of rule groups of recording and alerting rules.
     ///
     /// ```rust
-    /// use prometheus_http_query::{Client, Error, RuleType};
-    /// use std::convert::TryInto;
+    /// use prometheus_http_query::{Client, Error, RuleType};
+    /// use std::convert::TryInto;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.rules(None).await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     // Filter rules by type:
+    ///     let response = client.rules(Some(RuleType::Alert)).await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rules(&self, rule_type: Option<RuleType>) -> Result<Vec<RuleGroup>, Error> {
+        let filter = RuleFilter {
+            rule_type,
+            ..Default::default()
+        };
+
+        self.rules_filtered(&filter).await.map(|r| r.groups)
+    }
+
+    /// Retrieve rule groups of recording and alerting rules that match a [RuleFilter],
+    /// optionally one page at a time.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error, RuleFilter, RuleType};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let filter = RuleFilter::new()
+    ///         .rule_type(RuleType::Alert)
+    ///         .exclude_alerts(true)
+    ///         .group_limit(50);
+    ///
+    ///     let mut page = client.rules_filtered(&filter).await?;
+    ///     let mut groups = page.groups().len();
+    ///
+    ///     while let Some(token) = page.next_token().map(|t| t.to_string()) {
+    ///         let filter = RuleFilter::new()
+    ///             .rule_type(RuleType::Alert)
+    ///             .exclude_alerts(true)
+    ///             .group_limit(50)
+    ///             .group_next_token(&token);
+    ///
+    ///         page = client.rules_filtered(&filter).await?;
+    ///         groups += page.groups().len();
+    ///     }
+    ///
+    ///     println!("{} groups of alerting rules", groups);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rules_filtered(&self, filter: &RuleFilter<'_>) -> Result<RuleGroups, Error> {
+        let url = format!("{}/rules", self.base_url);
+
+        let mut params = vec![];
+
+        let rule_type = filter.rule_type.map(|s| s.to_string());
+
+        if let Some(s) = &rule_type {
+            params.push(("type", s.as_str()))
+        }
+
+        for name in &filter.rule_names {
+            params.push(("rule_name[]", name))
+        }
+
+        for group in &filter.rule_groups {
+            params.push(("rule_group[]", group))
+        }
+
+        for file in &filter.files {
+            params.push(("file[]", file))
+        }
+
+        if filter.exclude_alerts {
+            params.push(("exclude_alerts", "true"))
+        }
+
+        let selectors: Vec<String> = filter.selectors.iter().map(|s| s.to_string()).collect();
+
+        for selector in &selectors {
+            params.push(("match[]", selector))
+        }
+
+        let group_limit = filter.group_limit.map(|l| l.to_string());
+
+        if let Some(l) = &group_limit {
+            params.push(("group_limit", l.as_str()))
+        }
+
+        if let Some(t) = filter.group_next_token {
+            params.push(("group_next_token", t))
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .query(params.as_slice())
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
+            let result: RuleGroups = serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(result)
+        })
+    }
+
+    /// Retrieve a list of active alerts.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    /// use std::convert::TryInto;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.alerts().await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn alerts(&self) -> Result<Vec<Alert>, Error> {
+        let url = format!("{}/alerts", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let alerts = r
+                .get("data")
+                .ok_or(Error::MissingField)?
+                .as_object()
+                .unwrap()
+                .get("alerts")
+                .ok_or(Error::MissingField)?
+                .to_owned();
+
+            let result: Vec<Alert> =
+                serde_json::from_value(alerts).map_err(Error::ResponseParse)?;
+
+            Ok(result)
+        })
+    }
+
+    /// Retrieve a list of flags that Prometheus was configured with.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    /// use std::convert::TryInto;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.flags().await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn flags(&self) -> Result<HashMap<String, String>, Error> {
+        let url = format!("{}/status/flags", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
+            let flags: HashMap<String, String> =
+                serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(flags)
+        })
+    }
+
+    /// Retrieve the currently loaded configuration file of the Prometheus server.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.config().await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn config(&self) -> Result<Config, Error> {
+        let url = format!("{}/status/config", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
+            let config: Config = serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(config)
+        })
+    }
+
+    /// Retrieve runtime information about the Prometheus server.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.runtime_information().await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn runtime_information(&self) -> Result<RuntimeInformation, Error> {
+        let url = format!("{}/status/runtimeinfo", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
+            let result: RuntimeInformation =
+                serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(result)
+        })
+    }
+
+    /// Retrieve build information about the Prometheus server.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.build_information().await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn build_information(&self) -> Result<BuildInformation, Error> {
+        let url = format!("{}/status/buildinfo", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
+            let result: BuildInformation =
+                serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(result)
+        })
+    }
+
+    /// Retrieve cardinality statistics about the TSDB of the Prometheus server.
+    /// Use `limit` to restrict the number of items returned per statistic (defaults to 10).
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.tsdb_statistics(None).await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     // Retrieve the top 20 items per statistic instead.
+    ///     let response = client.tsdb_statistics(Some(20)).await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn tsdb_statistics(&self, limit: Option<usize>) -> Result<TsdbStatistics, Error> {
+        let url = format!("{}/status/tsdb", self.base_url);
+
+        let mut params = vec![];
+
+        let limit = limit.map(|s| s.to_string());
+
+        if let Some(l) = &limit {
+            params.push(("limit", l.as_str()))
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .query(params.as_slice())
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
+            let result: TsdbStatistics =
+                serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(result)
+        })
+    }
+
+    /// Retrieve the progress of the replay of the write-ahead log, e.g. after a restart of the Prometheus server.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.wal_replay_statistics().await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn wal_replay_statistics(&self) -> Result<WalReplayStatistics, Error> {
+        let url = format!("{}/status/walreplay", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        check_response(response).await.and_then(move |r| {
+            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
+            let result: WalReplayStatistics =
+                serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(result)
+        })
+    }
+
+    /// Create a snapshot of all current data of the TSDB. Use `skip_head` to skip data
+    /// that is only present in the head block and not yet compacted to disk.
+    ///
+    /// This requires the admin API to be enabled, otherwise [Error::AdminApiDisabled] is returned.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     match client.snapshot(false).await {
+    ///         Ok(snapshot) => println!("created snapshot {}", snapshot.name()),
+    ///         Err(Error::AdminApiDisabled) => println!("admin API is disabled"),
+    ///         Err(e) => return Err(e),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn snapshot(&self, skip_head: bool) -> Result<Snapshot, Error> {
+        let url = format!("{}/admin/tsdb/snapshot", self.base_url);
+
+        let skip_head = skip_head.to_string();
+
+        let params = vec![("skip_head", skip_head.as_str())];
+
+        let response = self
+            .client
+            .post(&url)
+            .query(params.as_slice())
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        check_admin_response(response).await.and_then(move |r| {
+            let data = r
+                .ok_or(Error::MissingField)?
+                .get("data")
+                .ok_or(Error::MissingField)?
+                .to_owned();
+            let result: Snapshot = serde_json::from_value(data).map_err(Error::ResponseParse)?;
+            Ok(result)
+        })
+    }
+
+    /// Delete time series that match certain label sets ([Selector]s) within an optional time range.
+    /// The data is not removed from disk immediately, but marked for deletion ("tombstoned") until
+    /// the next compaction or until [Client::clean_tombstones] is called.
+    ///
+    /// Set `dry_run` to only list the affected series by means of [Client::series] without
+    /// deleting anything.
+    ///
+    /// This requires the admin API to be enabled, otherwise [Error::AdminApiDisabled] is returned.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::response::SeriesDeletion;
+    /// use prometheus_http_query::{Client, Error, Selector};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let s = Selector::new()
+    ///         .metric("up")
+    ///         .with("job", "misconfigured");
+    ///
+    ///     // Check which series would be deleted first.
+    ///     let result = client.delete_series(&[s], None, None, true).await?;
+    ///
+    ///     if let SeriesDeletion::DryRun(series) = result {
+    ///         for labels in &series {
+    ///             println!("{:?}", labels);
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn delete_series(
+        &self,
+        selectors: &[Selector<'_>],
+        start: Option<i64>,
+        end: Option<i64>,
+        dry_run: bool,
+    ) -> Result<SeriesDeletion, Error> {
+        if selectors.is_empty() {
+            return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                message: String::from("at least one match[] argument (Selector) must be provided in order to delete series")
+            }));
+        }
+
+        if dry_run {
+            let affected = self.series(selectors, start, end, None).await?.into_inner();
+            return Ok(SeriesDeletion::DryRun(affected));
+        }
+
+        let url = format!("{}/admin/tsdb/delete_series", self.base_url);
+
+        let mut params = vec![];
+
+        let start = start.map(|t| t.to_string());
+
+        if let Some(s) = &start {
+            params.push(("start", s.as_str()));
+        }
+
+        let end = end.map(|t| t.to_string());
+
+        if let Some(e) = &end {
+            params.push(("end", e.as_str()));
+        }
+
+        let selectors = match_params(selectors);
+
+        for selector in &selectors {
+            params.push(("match[]", selector));
+        }
+
+        let response = self
+            .client
+            .post(&url)
+            .query(params.as_slice())
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        check_admin_response(response)
+            .await
+            .map(|_| SeriesDeletion::Deleted)
+    }
+
+    /// Remove data that was previously deleted by means of [Client::delete_series] from disk.
+    ///
+    /// This requires the admin API to be enabled, otherwise [Error::AdminApiDisabled] is returned.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let response = client.clean_tombstones().await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn clean_tombstones(&self) -> Result<(), Error> {
+        let url = format!("{}/admin/tsdb/clean_tombstones", self.base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        check_admin_response(response).await.map(|_| ())
+    }
+
+    /// Check if the Prometheus server is healthy. Returns `false` if the server
+    /// responds, but reports that it is unhealthy.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     let healthy = client.healthy().await?;
+    ///
+    ///     assert!(healthy);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn healthy(&self) -> Result<bool, Error> {
+        let url = self.management_url("healthy");
+        self.probe(&url, None).await
+    }
+
+    /// Check if the Prometheus server is ready to serve traffic (i.e. respond to queries).
+    /// Returns `false` if the server responds, but is not ready yet, e.g. because
+    /// the write-ahead log is still replayed.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
     ///
     /// #[tokio::main(flavor = "current_thread")]
     /// async fn main() -> Result<(), Error> {
     ///     let client = Client::default();
     ///
-    ///     let response = client.rules(None).await;
+    ///     let ready = client.ready().await?;
     ///
-    ///     assert!(response.is_ok());
+    ///     assert!(ready);
     ///
-    ///     // Filter rules by type:
-    ///     let response = client.rules(Some(RuleType::Alert)).await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ready(&self) -> Result<bool, Error> {
+        let url = self.management_url("ready");
+        self.probe(&url, None).await
+    }
+
+    /// Poll the Prometheus server every `interval` (at least every 100ms) until it is ready to
+    /// serve traffic. Failed requests, e.g. because the server is still starting up and does
+    /// not accept connections yet, are retried as well.
     ///
-    ///     assert!(response.is_ok());
+    /// Returns [Error::ReadinessTimeout] if the server is not ready before `timeout` elapsed.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     client
+    ///         .wait_until_ready(Duration::from_secs(60), Duration::from_secs(1))
+    ///         .await?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn rules(&self, rule_type: Option<RuleType>) -> Result<Vec<RuleGroup>, Error> {
-        let url = format!("{}/rules", self.base_url);
+    pub async fn wait_until_ready(
+        &self,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<(), Error> {
+        let url = self.management_url("ready");
+        let deadline = Instant::now() + timeout;
 
-        let mut params = vec![];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
 
-        let rule_type = rule_type.map(|s| s.to_string());
+            if remaining.is_zero() {
+                return Err(Error::ReadinessTimeout);
+            }
 
-        if let Some(s) = &rule_type {
-            params.push(("type", s.as_str()))
+            if let Ok(true) = self.probe(&url, Some(remaining)).await {
+                return Ok(());
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            tokio::time::sleep(interval.max(MIN_READINESS_INTERVAL).min(remaining)).await;
         }
+    }
+
+    /// Trigger a reload of the configuration and rule files of the Prometheus server.
+    /// Returns [Error::ConfigReloadFailed] including the reason if the reload was not successful.
+    ///
+    /// This requires the lifecycle API to be enabled, otherwise [Error::LifecycleApiDisabled] is returned.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::{Client, Error};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), Error> {
+    ///     let client = Client::default();
+    ///
+    ///     match client.reload().await {
+    ///         Ok(()) => println!("configuration reloaded"),
+    ///         Err(Error::ConfigReloadFailed(e)) => println!("{}", e),
+    ///         Err(e) => return Err(e),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn reload(&self) -> Result<(), Error> {
+        let url = self.management_url("reload");
 
         let response = self
             .client
-            .get(&url)
-            .query(params.as_slice())
+            .post(&url)
             .send()
             .await
-            .map_err(Error::Reqwest)?
-            .error_for_status()
             .map_err(Error::Reqwest)?;
 
-        check_response(response).await.and_then(move |r| {
-            let groups = r
-                .get("data")
-                .ok_or(Error::MissingField)?
-                .as_object()
-                .unwrap()
-                .get("groups")
-                .ok_or(Error::MissingField)?
-                .to_owned();
-
-            let result: Vec<RuleGroup> =
-                serde_json::from_value(groups).map_err(Error::ResponseParse)?;
-
-            Ok(result)
-        })
+        match response.status() {
+            reqwest::StatusCode::FORBIDDEN => Err(Error::LifecycleApiDisabled),
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR => {
+                let message = response.text().await.map_err(Error::Reqwest)?;
+                Err(Error::ConfigReloadFailed(ConfigReloadFailed {
+                    message: message.trim().to_string(),
+                }))
+            }
+            _ => response
+                .error_for_status()
+                .map(|_| ())
+                .map_err(Error::Reqwest),
+        }
     }
 
-    /// Retrieve a list of active alerts.
+    /// Trigger a graceful shutdown of the Prometheus server.
     ///
-    /// ```rust
+    /// This requires the lifecycle API to be enabled, otherwise [Error::LifecycleApiDisabled] is returned.
+    ///
+    /// ```rust,no_run
     /// use prometheus_http_query::{Client, Error};
-    /// use std::convert::TryInto;
     ///
     /// #[tokio::main(flavor = "current_thread")]
     /// async fn main() -> Result<(), Error> {
     ///     let client = Client::default();
     ///
-    ///     let response = client.alerts().await;
-    ///
-    ///     assert!(response.is_ok());
+    ///     client.quit().await?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn alerts(&self) -> Result<Vec<Alert>, Error> {
-        let url = format!("{}/alerts", self.base_url);
+    pub async fn quit(&self) -> Result<(), Error> {
+        let url = self.management_url("quit");
 
         let response = self
             .client
-            .get(&url)
+            .post(&url)
             .send()
             .await
-            .map_err(Error::Reqwest)?
-            .error_for_status()
             .map_err(Error::Reqwest)?;
 
-        check_response(response).await.and_then(move |r| {
-            let alerts = r
-                .get("data")
-                .ok_or(Error::MissingField)?
-                .as_object()
-                .unwrap()
-                .get("alerts")
-                .ok_or(Error::MissingField)?
-                .to_owned();
-
-            let result: Vec<Alert> =
-                serde_json::from_value(alerts).map_err(Error::ResponseParse)?;
-
-            Ok(result)
-        })
+        match response.status() {
+            reqwest::StatusCode::FORBIDDEN => Err(Error::LifecycleApiDisabled),
+            _ => response
+                .error_for_status()
+                .map(|_| ())
+                .map_err(Error::Reqwest),
+        }
     }
 
-    /// Retrieve a list of flags that Prometheus was configured with.
+    /// Retrieve the current values of all time series that match at least one of the
+    /// given [Selector]s from the `/federate` endpoint. The response is parsed from the
+    /// Prometheus text exposition format (see [crate::exposition]).
     ///
     /// ```rust
-    /// use prometheus_http_query::{Client, Error};
-    /// use std::convert::TryInto;
+    /// use prometheus_http_query::{Client, Error, Selector};
     ///
     /// #[tokio::main(flavor = "current_thread")]
     /// async fn main() -> Result<(), Error> {
     ///     let client = Client::default();
     ///
-    ///     let response = client.flags().await;
+    ///     let s = Selector::new().with("job", "prometheus");
+    ///
+    ///     let response = client.federate(&[s]).await;
     ///
     ///     assert!(response.is_ok());
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn flags(&self) -> Result<HashMap<String, String>, Error> {
-        let url = format!("{}/status/flags", self.base_url);
+    pub async fn federate(&self, selectors: &[Selector<'_>]) -> Result<Vec<MetricFamily>, Error> {
+        let url = format!("{}/federate", self.root_url());
+
+        if selectors.is_empty() {
+            return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                message: String::from("at least one match[] argument (Selector) must be provided in order to query the federation endpoint")
+            }));
+        }
+
+        let selectors = match_params(selectors);
+
+        let params: Vec<(&str, &str)> = selectors.iter().map(|s| ("match[]", s.as_str())).collect();
 
         let response = self
             .client
             .get(&url)
+            .header(reqwest::header::ACCEPT, "text/plain;version=0.0.4")
+            .query(params.as_slice())
             .send()
             .await
             .map_err(Error::Reqwest)?
             .error_for_status()
             .map_err(Error::Reqwest)?;
 
-        check_response(response).await.and_then(move |r| {
-            let data = r.get("data").ok_or(Error::MissingField)?.to_owned();
-            let flags: HashMap<String, String> =
-                serde_json::from_value(data).map_err(Error::ResponseParse)?;
-            Ok(flags)
-        })
+        let body = response.text().await.map_err(Error::Reqwest)?;
+
+        exposition::parse_text(&body)
+    }
+
+    // Send a GET request to a health or readiness endpoint. The server responds with
+    // "503 Service Unavailable" if it is up, but not healthy/ready.
+    async fn probe(&self, url: &str, timeout: Option<Duration>) -> Result<bool, Error> {
+        let mut request = self.client.get(url);
+
+        if let Some(t) = timeout {
+            request = request.timeout(t);
+        }
+
+        let response = request.send().await.map_err(Error::Reqwest)?;
+
+        match response.status() {
+            reqwest::StatusCode::SERVICE_UNAVAILABLE => Ok(false),
+            _ => response
+                .error_for_status()
+                .map(|_| true)
+                .map_err(Error::Reqwest),
+        }
     }
 
     /// Query the current state of alertmanager discovery.
@@ -910,17 +2000,22 @@ impl Client {
     ///     let client = Client::default();
     ///
     ///     // Retrieve metadata for a all metrics.
-    ///     let response = client.metric_metadata(None, None).await;
+    ///     let response = client.metric_metadata(None, None, None).await;
     ///
     ///     assert!(response.is_ok());
     ///
     ///     // Limit the number of returned metrics
-    ///     let response = client.metric_metadata(None, Some(10)).await;
+    ///     let response = client.metric_metadata(None, Some(10), None).await;
+    ///
+    ///     assert!(response.is_ok());
+    ///
+    ///     // Limit the number of metadata entries per metric
+    ///     let response = client.metric_metadata(None, None, Some(1)).await;
     ///
     ///     assert!(response.is_ok());
     ///
     ///     // Retrieve metadata of a specific metric.
-    ///     let response = client.metric_metadata(Some("go_routines"), None).await;
+    ///     let response = client.metric_metadata(Some("go_routines"), None, None).await;
     ///
     ///     assert!(response.is_ok());
     ///
@@ -931,6 +2026,7 @@ impl Client {
         &self,
         metric: Option<&str>,
         limit: Option<usize>,
+        limit_per_metric: Option<usize>,
     ) -> Result<HashMap<String, Vec<MetricMetadata>>, Error> {
         let url = format!("{}/metadata", self.base_url);
 
@@ -948,6 +2044,12 @@ impl Client {
             params.push(("limit", l.as_str()))
         }
 
+        let limit_per_metric = limit_per_metric.map(|s| s.to_string());
+
+        if let Some(l) = &limit_per_metric {
+            params.push(("limit_per_metric", l.as_str()))
+        }
+
         let response = self
             .client
             .get(&url)
@@ -970,7 +2072,7 @@ impl Client {
 
 // Convert the response object to an intermediary map, check the JSON's status field
 // and map potential errors (if any) to a proper error type. Else return the map.
-async fn check_response(
+pub(crate) async fn check_response(
     response: reqwest::Response,
 ) -> Result<HashMap<String, serde_json::Value>, Error> {
     let map = response
@@ -1009,45 +2111,454 @@ async fn check_response(
     }
 }
 
-// Parses the API response from a map to a Response enum that
-// encapsulates a result type of "vector", "matrix", or "scalar".
-fn convert_query_response(
-    response: HashMap<String, serde_json::Value>,
-) -> Result<QueryResultType, Error> {
-    let data_obj = response
-        .get("data")
-        .ok_or(Error::MissingField)?
-        .as_object()
-        .unwrap();
+// Convert selectors into `match[]` arguments, cutting off everything after the label
+// matchers, e.g. range durations and offsets.
+fn match_params(selectors: &[Selector<'_>]) -> Vec<String> {
+    selectors
+        .iter()
+        .map(|s| {
+            let s = s.to_string();
+            match s.split_once('}') {
+                Some((matchers, _)) => format!("{}}}", matchers),
+                None => s,
+            }
+        })
+        .collect()
+}
 
-    let data_type = data_obj
-        .get("resultType")
-        .ok_or(Error::MissingField)?
-        .as_str()
-        .unwrap();
+// Collect the warnings of a successful response, e.g. about results truncated by a limit.
+fn warnings(map: &HashMap<String, serde_json::Value>) -> Vec<String> {
+    map.get("warnings")
+        .and_then(|w| w.as_array())
+        .map(|w| {
+            w.iter()
+                .filter_map(|w| w.as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
-    let data = data_obj
-        .get("result")
-        .ok_or(Error::MissingField)?
-        .to_owned();
+// Admin endpoints respond with "204 No Content" on success unless they return data.
+// On failure the JSON body still contains the error details, so it is checked before
+// falling back to the HTTP status in order to detect a disabled admin API.
+async fn check_admin_response(
+    response: reqwest::Response,
+) -> Result<Option<HashMap<String, serde_json::Value>>, Error> {
+    if response.status() == reqwest::StatusCode::NO_CONTENT {
+        return Ok(None);
+    }
 
-    match data_type {
-        "vector" => {
-            let result: Vec<InstantVector> =
-                serde_json::from_value(data).map_err(Error::ResponseParse)?;
-            Ok(QueryResultType::Vector(result))
+    let status_error = response.error_for_status_ref().err();
+
+    match check_response(response).await {
+        Ok(map) => match status_error {
+            Some(e) => Err(Error::Reqwest(e)),
+            None => Ok(Some(map)),
+        },
+        Err(Error::ResponseError(e))
+            if e.kind == "unavailable" && e.message.contains("admin APIs disabled") =>
+        {
+            Err(Error::AdminApiDisabled)
         }
-        "matrix" => {
-            let result: Vec<RangeVector> =
-                serde_json::from_value(data).map_err(Error::ResponseParse)?;
-            Ok(QueryResultType::Matrix(result))
+        Err(e) => match status_error {
+            Some(status_error) if !matches!(e, Error::ResponseError(_)) => {
+                Err(Error::Reqwest(status_error))
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+// The envelope of query responses. Unlike other responses, it is deserialized directly from
+// the buffered response body rather than from an intermediary map, as converting huge results
+// into a map first would roughly double the peak memory usage. The body itself is still read
+// completely, see [SeriesStream] for decoding results while they are received.
+#[derive(Deserialize)]
+struct QueryResponse {
+    status: Option<String>,
+    data: Option<QueryData>,
+    #[serde(rename = "errorType")]
+    error_type: Option<String>,
+    error: Option<String>,
+}
+
+struct QueryData {
+    result_type: Option<String>,
+    result: Option<QueryResultType>,
+    stats: Option<QueryStats>,
+}
+
+impl<'de> Deserialize<'de> for QueryData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct QueryDataVisitor;
+
+        impl<'de> Visitor<'de> for QueryDataVisitor {
+            type Value = QueryData;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("the data of a query response")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut result_type: Option<String> = None;
+                let mut result = None;
+                let mut raw_result: Option<serde_json::Value> = None;
+                let mut stats = None;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "resultType" => result_type = Some(map.next_value()?),
+                        // Prometheus sends the type first, so the result can be deserialized
+                        // right away. Otherwise it needs to be buffered until the type is known.
+                        "result" => match result_type.as_deref() {
+                            Some("vector") => {
+                                result = Some(QueryResultType::Vector(map.next_value()?))
+                            }
+                            Some("matrix") => {
+                                result = Some(QueryResultType::Matrix(map.next_value()?))
+                            }
+                            Some("scalar") => {
+                                result = Some(QueryResultType::Scalar(map.next_value()?))
+                            }
+                            Some("string") => {
+                                result = Some(QueryResultType::String(map.next_value()?))
+                            }
+                            Some(_) => {
+                                map.next_value::<IgnoredAny>()?;
+                            }
+                            None => raw_result = Some(map.next_value()?),
+                        },
+                        "stats" => stats = map.next_value()?,
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                if let (Some(raw_result), Some(kind)) = (raw_result, result_type.as_deref()) {
+                    if matches!(kind, "vector" | "matrix" | "scalar" | "string") {
+                        let tagged =
+                            serde_json::json!({ "resultType": kind, "result": raw_result });
+                        result = Some(serde_json::from_value(tagged).map_err(de::Error::custom)?);
+                    }
+                }
+
+                Ok(QueryData {
+                    result_type,
+                    result,
+                    stats,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(QueryDataVisitor)
+    }
+}
+
+async fn parse_query_response(
+    response: reqwest::Response,
+) -> Result<(QueryResultType, Option<QueryStats>), Error> {
+    let body = response.bytes().await.map_err(Error::Reqwest)?;
+
+    let response: QueryResponse = serde_json::from_slice(&body).map_err(Error::ResponseParse)?;
+
+    match response.status.as_deref().ok_or(Error::MissingField)? {
+        "success" => {
+            let data = response.data.ok_or(Error::MissingField)?;
+
+            match (data.result, data.result_type) {
+                (Some(result), _) => Ok((result, data.stats)),
+                (None, Some(kind))
+                    if !matches!(kind.as_str(), "vector" | "matrix" | "scalar" | "string") =>
+                {
+                    Err(Error::UnsupportedQueryResultType(
+                        UnsupportedQueryResultType(kind),
+                    ))
+                }
+                _ => Err(Error::MissingField),
+            }
+        }
+        "error" => Err(Error::ResponseError(ResponseError {
+            kind: response.error_type.ok_or(Error::MissingField)?,
+            message: response.error.ok_or(Error::MissingField)?,
+        })),
+        status => Err(Error::UnknownResponseStatus(UnknownResponseStatus(
+            status.to_string(),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server;
+    use crate::version::Version;
+    use futures_util::StreamExt;
+    use std::str::FromStr;
+
+    // A Prometheus server that responds to all requests with the given body.
+    async fn prometheus(body: &'static str) -> (Client, test_server::Requests) {
+        let (url, received) = test_server::start(200, body).await;
+
+        let client = Client {
+            client: reqwest::Client::new(),
+            base_url: format!("{}/api/v1", url),
+            capabilities: None,
+        };
+
+        (client, received)
+    }
+
+    #[test]
+    fn test_management_url() {
+        let client = Client::default();
+
+        assert_eq!(
+            client.management_url("ready"),
+            "http://127.0.0.1:9090/-/ready"
+        );
+
+        let client = Client::from_str("https://proxy.example.com:8443/prometheus").unwrap();
+
+        assert_eq!(
+            client.management_url("reload"),
+            "https://proxy.example.com:8443/prometheus/-/reload"
+        );
+
+        let client = Client::from_str("https://prometheus.example.com").unwrap();
+
+        assert_eq!(
+            client.management_url("healthy"),
+            "https://prometheus.example.com/-/healthy"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_series() {
+        let (client, received) =
+            prometheus(r#"{"status":"success","data":[{"__name__":"up","job":"node"}]}"#).await;
+
+        let selector = Selector::new().metric("up").with("job", "node");
+
+        let result = client
+            .delete_series(std::slice::from_ref(&selector), None, None, true)
+            .await
+            .unwrap();
+
+        match result {
+            SeriesDeletion::DryRun(series) => assert_eq!(series[0]["job"], "node"),
+            SeriesDeletion::Deleted => panic!("expected a dry run"),
         }
-        "scalar" => {
-            let result: Sample = serde_json::from_value(data).map_err(Error::ResponseParse)?;
-            Ok(QueryResultType::Scalar(result))
+
+        let result = client
+            .delete_series(&[selector], None, None, false)
+            .await
+            .unwrap();
+
+        assert_eq!(result, SeriesDeletion::Deleted);
+
+        let received = received.lock().unwrap();
+
+        assert_eq!(received.len(), 2);
+        assert!(received[0].request_line.starts_with("GET /api/v1/series?"));
+        assert!(received[1].request_line.starts_with(
+            "POST /api/v1/admin/tsdb/delete_series?match%5B%5D=%7B__name__%3D%22up%22%2Cjob%3D%22node%22%7D "
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_series_for_error() {
+        let (client, received) = prometheus(r#"{"status":"success","data":[]}"#).await;
+
+        for dry_run in [true, false] {
+            let result = client.delete_series(&[], None, None, dry_run).await;
+
+            assert!(matches!(result, Err(Error::InvalidFunctionArgument(_))));
         }
-        _ => Err(Error::UnsupportedQueryResultType(
-            UnsupportedQueryResultType(data_type.to_string()),
-        )),
+
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_with_zero_interval() {
+        let (url, received) = test_server::start(503, "Service Unavailable").await;
+        let client = Client::from_str(&url).unwrap();
+
+        let result = client
+            .wait_until_ready(Duration::from_millis(350), Duration::ZERO)
+            .await;
+
+        assert!(matches!(result, Err(Error::ReadinessTimeout)));
+        assert!(received.lock().unwrap().len() <= 4);
+    }
+
+    #[tokio::test]
+    async fn test_series_with_limit() {
+        let (client, received) = prometheus(
+            r#"{"status":"success","data":[{"__name__":"up","job":"node"}],"warnings":["results truncated due to limit"]}"#,
+        )
+        .await;
+
+        let selectors = vec![Selector::new().metric("up")];
+
+        let response = client
+            .series(&selectors, None, None, Some(1))
+            .await
+            .unwrap();
+
+        assert!(response.is_truncated());
+        assert_eq!(response.len(), 1);
+        assert_eq!(response[0].get("job").unwrap(), "node");
+        assert!(received.lock().unwrap()[0].request_line.contains("limit=1"));
+
+        let (client, _) = prometheus(r#"{"status":"success","data":["job"]}"#).await;
+
+        let response = client.label_names(None, None, None, Some(1)).await.unwrap();
+
+        assert!(!response.is_truncated());
+        assert_eq!(response.into_inner(), vec!["job".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_rules_filtered() {
+        let (client, received) =
+            prometheus(r#"{"status":"success","data":{"groups":[],"groupNextToken":"abc"}}"#).await;
+
+        let filter = RuleFilter::new()
+            .rule_type(RuleType::Alert)
+            .rule_name("HighLoad")
+            .file("node.yml")
+            .exclude_alerts(true)
+            .group_limit(1)
+            .group_next_token("xyz");
+
+        let response = client.rules_filtered(&filter).await.unwrap();
+
+        assert!(response.groups().is_empty());
+        assert_eq!(response.next_token(), Some("abc"));
+        assert_eq!(
+            received.lock().unwrap()[0].request_line,
+            "GET /api/v1/rules?type=alert&rule_name%5B%5D=HighLoad&file%5B%5D=node.yml&exclude_alerts=true&group_limit=1&group_next_token=xyz HTTP/1.1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_with_unsupported_function() {
+        let (client, received) = prometheus(
+            r#"{"status":"success","data":{"resultType":"scalar","result":[1435781451.781,"1"]}}"#,
+        )
+        .await;
+
+        let client = client.with_capabilities(Capabilities::new(Version::new(2, 28, 0)));
+
+        let result = client.query("present_over_time(up[5m])", None, None).await;
+
+        assert!(matches!(result, Err(Error::UnsupportedFunction(_))));
+        assert!(received.lock().unwrap().is_empty());
+
+        assert!(client.query("pi()", None, None).await.is_err());
+        assert!(client.query("time()", None, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_detect_capabilities_of_other_servers() {
+        let (mut client, _) = prometheus(
+            r#"{"status":"success","data":{"version":"0.34.0","revision":"","branch":"","buildUser":"","buildDate":"","goVersion":"go1.21"}}"#,
+        )
+        .await;
+
+        assert!(client.detect_capabilities().await.unwrap().is_none());
+        assert!(client.check_capabilities("sin(up)").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_query_response_parsing() {
+        // The result may precede its type, in which case it is buffered.
+        let (client, _) = prometheus(
+            r#"{"status":"success","data":{"result":[{"metric":{"job":"node"},"value":[1435781451.781,"1"]}],"resultType":"vector","stats":{"timings":{"evalTotalTime":0.1,"resultSortTime":0,"queryPreparationTime":0.1,"innerEvalTime":0.1,"execQueueTime":0.1,"execTotalTime":0.1},"samples":{"totalQueryableSamples":1,"peakSamples":1}}}}"#,
+        )
+        .await;
+
+        let (result, stats) = client.query_with_stats("up", None, None).await.unwrap();
+
+        assert_eq!(result.as_instant().unwrap().len(), 1);
+        assert_eq!(stats.samples().peak_samples(), 1);
+
+        let (client, _) =
+            prometheus(r#"{"status":"success","data":{"resultType":"histogram","result":[]}}"#)
+                .await;
+
+        assert!(matches!(
+            client.query("up", None, None).await,
+            Err(Error::UnsupportedQueryResultType(_))
+        ));
+
+        let (client, _) =
+            prometheus(r#"{"status":"success","data":{"resultType":"vector"}}"#).await;
+
+        assert!(matches!(
+            client.query("up", None, None).await,
+            Err(Error::MissingField)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_query_range_stream() {
+        let (client, received) = prometheus(
+            r#"{"status":"success","data":{"resultType":"matrix","result":[{"metric":{"job":"node"},"values":[[1435781430.781,"1"]]},{"metric":{"job":"prometheus"},"values":[[1435781430.781,"0"]]}]},"warnings":["results truncated"]}"#,
+        )
+        .await;
+
+        let mut series = client
+            .query_range_stream("up", 1435781430, 1435781460, Some("15s"), None)
+            .await
+            .unwrap();
+
+        let first = series.next().await.unwrap().unwrap();
+        let second = series.next().await.unwrap().unwrap();
+
+        assert_eq!(first.metric().get("job").unwrap(), "node");
+        assert_eq!(second.samples()[0].value(), 0.0);
+        assert!(series.next().await.is_none());
+        assert_eq!(series.warnings(), ["results truncated"]);
+        assert!(received.lock().unwrap()[0]
+            .request_line
+            .contains("/query_range?"));
+
+        let series = client.query_stream("up", None, None).await.unwrap();
+        let series: Vec<_> = series.into_stream().collect().await;
+
+        assert_eq!(series.len(), 1);
+        assert!(matches!(
+            series[0],
+            Err(Error::UnsupportedQueryResultType(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_query_range_columnar() {
+        let (client, _) = prometheus(
+            r#"{"status":"success","data":{"resultType":"matrix","result":[{"metric":{"job":"node"},"values":[[1435781430.781,"1"],[1435781445.781,"NaN"]]}]},"warnings":["results truncated due to limit"]}"#,
+        )
+        .await;
+
+        let matrix = client
+            .query_range_columnar("up", 1435781430, 1435781460, Some("15s"), None)
+            .await
+            .unwrap();
+
+        assert_eq!(matrix.len(), 1);
+        assert_eq!(matrix[0].labels().get("job"), Some("node"));
+        assert_eq!(matrix[0].timestamps(), [1435781430781, 1435781445781]);
+        assert!(matrix[0].values()[1].is_nan());
+        assert!(matrix.is_truncated());
+        assert_eq!(matrix.warnings(), ["results truncated due to limit"]);
     }
 }
@@ -0,0 +1,327 @@
+//! A columnar representation of range query results for analytics.
+//!
+//! [crate::response::RangeVector]s store a timestamp and a value per [crate::response::Sample]
+//! as well as a map of labels per series. A [ColumnarMatrix] instead stores the timestamps
+//! (in milliseconds) and values of each series in two parallel vectors, and shares the label
+//! names and values between all series of the matrix.
+//!
+//! Use [crate::Client::query_range_columnar] to decode a range query result into this form
+//! straight away, or convert existing results using [ColumnarMatrix::from].
+//!
+//! ```rust
+//! use prometheus_http_query::columnar::ColumnarMatrix;
+//! use prometheus_http_query::response::{RangeVector, Sample};
+//! use std::collections::HashMap;
+//!
+//! let metric = HashMap::from([("job".to_string(), "node".to_string())]);
+//! let series = RangeVector::new(metric, vec![Sample::new(1435781430.781, 1.0), Sample::new(1435781445.781, 0.0)]);
+//!
+//! let matrix = ColumnarMatrix::from(vec![series]);
+//!
+//! assert_eq!(matrix[0].labels().get("job"), Some("node"));
+//! assert_eq!(matrix[0].timestamps(), [1435781430781, 1435781445781]);
+//! assert_eq!(matrix[0].values(), [1.0, 0.0]);
+//! ```
+use crate::error::{Error, InvalidFunctionArgument};
+use crate::response::{RangeVector, Sample};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// An immutable set of labels that is cheap to clone and shares its label names and values
+/// with other label sets of the same [ColumnarMatrix]. Labels are sorted by name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LabelSet(Arc<[(Arc<str>, Arc<str>)]>);
+
+impl LabelSet {
+    /// Get the value of a label.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .binary_search_by(|(n, _)| n.as_ref().cmp(name))
+            .ok()
+            .map(|i| self.0[i].1.as_ref())
+    }
+
+    /// Iterate over all label names and values.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(n, v)| (n.as_ref(), v.as_ref()))
+    }
+
+    /// Get the number of labels.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check whether this set contains no labels.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Convert this into a map of labels as used by [crate::response::RangeVector].
+    pub fn to_map(&self) -> HashMap<String, String> {
+        self.iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect()
+    }
+}
+
+// Deduplicates label names, values and sets so that equal ones share their allocation.
+#[derive(Debug, Default, Clone)]
+struct Interner {
+    strings: HashSet<Arc<str>>,
+    sets: HashSet<LabelSet>,
+}
+
+impl Interner {
+    fn string(&mut self, s: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(s) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(s);
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    fn labels<'a>(&mut self, labels: impl Iterator<Item = (&'a str, &'a str)>) -> LabelSet {
+        let mut pairs: Vec<(Arc<str>, Arc<str>)> = labels
+            .map(|(n, v)| (self.string(n), self.string(v)))
+            .collect();
+        pairs.sort();
+
+        let set = LabelSet(Arc::from(pairs));
+
+        if let Some(interned) = self.sets.get(&set) {
+            return interned.clone();
+        }
+        self.sets.insert(set.clone());
+        set
+    }
+}
+
+/// A single time series with its timestamps (in milliseconds) and values stored in two
+/// parallel vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnarSeries {
+    pub(crate) labels: LabelSet,
+    pub(crate) timestamps: Vec<i64>,
+    pub(crate) values: Vec<f64>,
+}
+
+impl ColumnarSeries {
+    /// Get the labels (+ metric name) of this time series.
+    pub fn labels(&self) -> &LabelSet {
+        &self.labels
+    }
+
+    /// Get the timestamps of all samples in milliseconds.
+    pub fn timestamps(&self) -> &[i64] {
+        &self.timestamps
+    }
+
+    /// Get the values of all samples.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Get the number of samples.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Check whether this series contains no samples.
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Iterate over all samples as pairs of timestamp (in milliseconds) and value.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, f64)> + '_ {
+        self.timestamps
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+    }
+}
+
+impl From<&ColumnarSeries> for RangeVector {
+    fn from(series: &ColumnarSeries) -> Self {
+        let samples = series
+            .iter()
+            .map(|(timestamp, value)| Sample::new(timestamp as f64 / 1000.0, value))
+            .collect();
+
+        RangeVector::new(series.labels.to_map(), samples)
+    }
+}
+
+/// The time series of a range query result in columnar form.
+///
+/// Note that only float samples are represented, native histogram samples of the
+/// original result are not included.
+#[derive(Debug, Default, Clone)]
+pub struct ColumnarMatrix {
+    pub(crate) series: Vec<ColumnarSeries>,
+    interner: Interner,
+}
+
+impl ColumnarMatrix {
+    /// Create an empty matrix.
+    pub fn new() -> Self {
+        ColumnarMatrix::default()
+    }
+
+    /// Add a time series from parallel vectors of timestamps (in milliseconds) and values.
+    pub fn push_columns<'a>(
+        &mut self,
+        labels: impl IntoIterator<Item = (&'a str, &'a str)>,
+        timestamps: Vec<i64>,
+        values: Vec<f64>,
+    ) -> Result<(), Error> {
+        if timestamps.len() != values.len() {
+            return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                message: format!(
+                    "got {} timestamps but {} values for a time series",
+                    timestamps.len(),
+                    values.len()
+                ),
+            }));
+        }
+
+        let labels = self.interner.labels(labels.into_iter());
+
+        self.series.push(ColumnarSeries {
+            labels,
+            timestamps,
+            values,
+        });
+
+        Ok(())
+    }
+
+    /// Add a time series of a range query result.
+    pub fn push(&mut self, series: &RangeVector) {
+        let labels = self
+            .interner
+            .labels(series.metric.iter().map(|(n, v)| (n.as_str(), v.as_str())));
+
+        let (timestamps, values) = series
+            .samples
+            .iter()
+            .map(|s| (s.timestamp_millis(), s.value))
+            .unzip();
+
+        self.series.push(ColumnarSeries {
+            labels,
+            timestamps,
+            values,
+        });
+    }
+
+    /// Get all time series of this matrix.
+    pub fn series(&self) -> &[ColumnarSeries] {
+        &self.series
+    }
+
+    /// Get the total number of samples of all time series.
+    pub fn sample_count(&self) -> usize {
+        self.series.iter().map(|s| s.len()).sum()
+    }
+
+    /// Convert this matrix into time series as returned by [crate::Client::query_range].
+    pub fn to_range_vectors(&self) -> Vec<RangeVector> {
+        self.series.iter().map(RangeVector::from).collect()
+    }
+
+    /// Convert this matrix into its time series.
+    pub fn into_series(self) -> Vec<ColumnarSeries> {
+        self.series
+    }
+}
+
+impl std::ops::Deref for ColumnarMatrix {
+    type Target = [ColumnarSeries];
+
+    fn deref(&self) -> &Self::Target {
+        &self.series
+    }
+}
+
+impl From<&[RangeVector]> for ColumnarMatrix {
+    fn from(series: &[RangeVector]) -> Self {
+        let mut matrix = ColumnarMatrix::new();
+        for s in series {
+            matrix.push(s);
+        }
+        matrix
+    }
+}
+
+impl From<Vec<RangeVector>> for ColumnarMatrix {
+    fn from(series: Vec<RangeVector>) -> Self {
+        ColumnarMatrix::from(series.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::range_vector;
+
+    #[test]
+    fn test_columnar_roundtrip() {
+        let series = vec![
+            range_vector(
+                "node",
+                Some("a:9100"),
+                &[(1435781430.781, 1.0), (1435781445.781, 0.0)],
+            ),
+            range_vector("node", Some("b:9100"), &[(1435781430.781, f64::INFINITY)]),
+        ];
+
+        let matrix = ColumnarMatrix::from(series.as_slice());
+
+        assert_eq!(matrix.len(), 2);
+        assert_eq!(matrix.sample_count(), 3);
+        assert_eq!(matrix[0].timestamps(), [1435781430781, 1435781445781]);
+        assert_eq!(matrix[1].values(), [f64::INFINITY]);
+        assert_eq!(
+            matrix[0]
+                .labels()
+                .iter()
+                .map(|(n, _)| n)
+                .collect::<Vec<_>>(),
+            vec!["__name__", "instance", "job"]
+        );
+        assert_eq!(matrix.to_range_vectors(), series);
+    }
+
+    #[test]
+    fn test_label_interning() {
+        let mut matrix = ColumnarMatrix::new();
+        matrix.push(&range_vector("node", Some("a:9100"), &[]));
+        matrix.push(&range_vector("node", Some("b:9100"), &[]));
+        matrix.push(&range_vector("node", Some("a:9100"), &[]));
+
+        let (first, second, third) = (
+            &matrix[0].labels().0,
+            &matrix[1].labels().0,
+            &matrix[2].labels().0,
+        );
+
+        assert!(Arc::ptr_eq(first, third));
+        assert!(!Arc::ptr_eq(first, second));
+        assert!(Arc::ptr_eq(&first[2].1, &second[2].1));
+    }
+
+    #[test]
+    fn test_push_columns_for_error() {
+        let mut matrix = ColumnarMatrix::new();
+
+        assert!(matrix
+            .push_columns([("job", "node")], vec![1, 2], vec![1.0, 2.0])
+            .is_ok());
+        assert!(matrix
+            .push_columns([("job", "node")], vec![1, 2], vec![1.0])
+            .is_err());
+        assert_eq!(matrix[0].labels().get("job"), Some("node"));
+        assert_eq!(matrix[0].labels().get("instance"), None);
+    }
+}
@@ -0,0 +1,792 @@
+//! A typed model of the Prometheus [configuration file](https://prometheus.io/docs/prometheus/latest/configuration/configuration/)
+//! as returned by [crate::Client::config] (see [crate::response::Config::parse]).
+//!
+//! Only the most relevant settings are modeled explicitly. All other settings are kept as
+//! [serde_yaml::Value]s (see e.g. [ScrapeConfig::other]) so that a configuration can be
+//! serialized back to YAML without loss.
+//!
+//! ```rust
+//! use prometheus_http_query::config::PrometheusConfig;
+//!
+//! let yaml = r#"
+//! global:
+//!   scrape_interval: 15s
+//! scrape_configs:
+//!   - job_name: node
+//!     scrape_interval: 30s
+//!     static_configs:
+//!       - targets: ["localhost:9100"]
+//!   - job_name: prometheus
+//!     static_configs:
+//!       - targets: ["localhost:9090"]
+//! "#;
+//!
+//! let config = PrometheusConfig::from_yaml(yaml).unwrap();
+//!
+//! for job in config.scrape_configs() {
+//!     println!("{}: {}", job.job_name(), config.scrape_interval_of(job));
+//! }
+//!
+//! assert_eq!(config.scrape_interval_of(&config.scrape_configs()[1]), "15s");
+//! ```
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Additional settings that are not modeled explicitly.
+pub type Other = BTreeMap<String, serde_yaml::Value>;
+
+/// The default scrape interval and evaluation interval of Prometheus.
+const DEFAULT_INTERVAL: &str = "1m";
+
+/// The complete configuration of a Prometheus server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrometheusConfig {
+    #[serde(default)]
+    pub(crate) global: GlobalConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) rule_files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) scrape_config_files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) alerting: Option<AlertingConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) scrape_configs: Vec<ScrapeConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) remote_write: Vec<RemoteWriteConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) remote_read: Vec<RemoteReadConfig>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl PrometheusConfig {
+    /// Parse a configuration from YAML.
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        serde_yaml::from_str(yaml).map_err(Error::YamlParse)
+    }
+
+    /// Serialize this configuration to YAML.
+    pub fn to_yaml(&self) -> Result<String, Error> {
+        serde_yaml::to_string(self).map_err(Error::YamlParse)
+    }
+
+    /// Get the global configuration.
+    pub fn global(&self) -> &GlobalConfig {
+        &self.global
+    }
+
+    /// Get the paths of the rule files.
+    pub fn rule_files(&self) -> &[String] {
+        &self.rule_files
+    }
+
+    /// Get the paths of files that contain additional scrape configurations.
+    pub fn scrape_config_files(&self) -> &[String] {
+        &self.scrape_config_files
+    }
+
+    /// Get the alerting configuration.
+    pub fn alerting(&self) -> Option<&AlertingConfig> {
+        self.alerting.as_ref()
+    }
+
+    /// Get the scrape configurations.
+    pub fn scrape_configs(&self) -> &[ScrapeConfig] {
+        &self.scrape_configs
+    }
+
+    /// Get the remote write configurations.
+    pub fn remote_write(&self) -> &[RemoteWriteConfig] {
+        &self.remote_write
+    }
+
+    /// Get the remote read configurations.
+    pub fn remote_read(&self) -> &[RemoteReadConfig] {
+        &self.remote_read
+    }
+
+    /// Get all other top-level settings, e.g. `storage` or `tracing`.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+
+    /// Find a scrape configuration by its job name.
+    pub fn scrape_config(&self, job_name: &str) -> Option<&ScrapeConfig> {
+        self.scrape_configs.iter().find(|s| s.job_name == job_name)
+    }
+
+    /// Get the scrape interval that is effective for a scrape configuration, i.e. its
+    /// own interval, the global one or the default of Prometheus (`1m`).
+    pub fn scrape_interval_of<'a>(&'a self, scrape_config: &'a ScrapeConfig) -> &'a str {
+        scrape_config
+            .scrape_interval
+            .as_deref()
+            .or(self.global.scrape_interval.as_deref())
+            .unwrap_or(DEFAULT_INTERVAL)
+    }
+}
+
+/// The global configuration that applies to all other configuration contexts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlobalConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scrape_interval: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scrape_timeout: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) evaluation_interval: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) external_labels: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl GlobalConfig {
+    /// Get the default scrape interval.
+    pub fn scrape_interval(&self) -> Option<&str> {
+        self.scrape_interval.as_deref()
+    }
+
+    /// Get the default scrape timeout.
+    pub fn scrape_timeout(&self) -> Option<&str> {
+        self.scrape_timeout.as_deref()
+    }
+
+    /// Get the interval in which rules are evaluated.
+    pub fn evaluation_interval(&self) -> Option<&str> {
+        self.evaluation_interval.as_deref()
+    }
+
+    /// Get the labels that are attached to series and alerts sent to external systems.
+    pub fn external_labels(&self) -> &BTreeMap<String, String> {
+        &self.external_labels
+    }
+
+    /// Get all other global settings.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+}
+
+/// A scrape configuration, i.e. a job.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScrapeConfig {
+    pub(crate) job_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) honor_labels: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) honor_timestamps: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scrape_interval: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scrape_timeout: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) metrics_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scheme: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) params: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) static_configs: Vec<StaticConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) file_sd_configs: Vec<FileSdConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) kubernetes_sd_configs: Vec<KubernetesSdConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) relabel_configs: Vec<RelabelConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) metric_relabel_configs: Vec<RelabelConfig>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl ScrapeConfig {
+    /// Get the job name.
+    pub fn job_name(&self) -> &str {
+        &self.job_name
+    }
+
+    /// Check whether labels of scraped metrics take precedence over target labels.
+    pub fn honor_labels(&self) -> Option<bool> {
+        self.honor_labels
+    }
+
+    /// Check whether the timestamps of scraped metrics are kept.
+    pub fn honor_timestamps(&self) -> Option<bool> {
+        self.honor_timestamps
+    }
+
+    /// Get the scrape interval of this job, if set (see [PrometheusConfig::scrape_interval_of]).
+    pub fn scrape_interval(&self) -> Option<&str> {
+        self.scrape_interval.as_deref()
+    }
+
+    /// Get the scrape timeout of this job, if set.
+    pub fn scrape_timeout(&self) -> Option<&str> {
+        self.scrape_timeout.as_deref()
+    }
+
+    /// Get the HTTP path metrics are scraped from.
+    pub fn metrics_path(&self) -> Option<&str> {
+        self.metrics_path.as_deref()
+    }
+
+    /// Get the URL scheme targets are scraped with.
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.as_deref()
+    }
+
+    /// Get the URL parameters of scrape requests.
+    pub fn params(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.params
+    }
+
+    /// Get the statically configured targets.
+    pub fn static_configs(&self) -> &[StaticConfig] {
+        &self.static_configs
+    }
+
+    /// Get the file-based service discovery configurations.
+    pub fn file_sd_configs(&self) -> &[FileSdConfig] {
+        &self.file_sd_configs
+    }
+
+    /// Get the Kubernetes service discovery configurations.
+    pub fn kubernetes_sd_configs(&self) -> &[KubernetesSdConfig] {
+        &self.kubernetes_sd_configs
+    }
+
+    /// Get all service discovery configurations that are not modeled explicitly,
+    /// e.g. `consul_sd_configs` or `ec2_sd_configs`, by their key.
+    pub fn sd_configs(&self) -> BTreeMap<&str, &serde_yaml::Value> {
+        self.other
+            .iter()
+            .filter(|(key, _)| key.ends_with("_sd_configs"))
+            .map(|(key, value)| (key.as_str(), value))
+            .collect()
+    }
+
+    /// Get the relabeling rules that are applied to targets before scraping.
+    pub fn relabel_configs(&self) -> &[RelabelConfig] {
+        &self.relabel_configs
+    }
+
+    /// Get the relabeling rules that are applied to scraped samples before ingestion.
+    pub fn metric_relabel_configs(&self) -> &[RelabelConfig] {
+        &self.metric_relabel_configs
+    }
+
+    /// Get all other settings of this job.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+}
+
+/// A set of statically configured targets.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StaticConfig {
+    #[serde(default)]
+    pub(crate) targets: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) labels: BTreeMap<String, String>,
+}
+
+impl StaticConfig {
+    /// Get the addresses of the targets.
+    pub fn targets(&self) -> &[String] {
+        &self.targets
+    }
+
+    /// Get the labels that are attached to all targets.
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+}
+
+/// A file-based service discovery configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileSdConfig {
+    #[serde(default)]
+    pub(crate) files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) refresh_interval: Option<String>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl FileSdConfig {
+    /// Get the file patterns targets are read from.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// Get the interval in which the files are re-read.
+    pub fn refresh_interval(&self) -> Option<&str> {
+        self.refresh_interval.as_deref()
+    }
+
+    /// Get all other settings.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+}
+
+/// A Kubernetes service discovery configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KubernetesSdConfig {
+    pub(crate) role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) api_server: Option<String>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl KubernetesSdConfig {
+    /// Get the role of the discovered entities, e.g. `pod` or `endpoints`.
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    /// Get the address of the Kubernetes API server, if not running in-cluster.
+    pub fn api_server(&self) -> Option<&str> {
+        self.api_server.as_deref()
+    }
+
+    /// Get all other settings, e.g. `namespaces` or `selectors`.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+}
+
+/// Possible actions of a relabeling rule.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    Replace,
+    Keep,
+    Drop,
+    KeepEqual,
+    DropEqual,
+    HashMod,
+    LabelMap,
+    LabelDrop,
+    LabelKeep,
+    Lowercase,
+    Uppercase,
+}
+
+impl fmt::Display for RelabelAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelabelAction::Replace => write!(f, "replace"),
+            RelabelAction::Keep => write!(f, "keep"),
+            RelabelAction::Drop => write!(f, "drop"),
+            RelabelAction::KeepEqual => write!(f, "keepequal"),
+            RelabelAction::DropEqual => write!(f, "dropequal"),
+            RelabelAction::HashMod => write!(f, "hashmod"),
+            RelabelAction::LabelMap => write!(f, "labelmap"),
+            RelabelAction::LabelDrop => write!(f, "labeldrop"),
+            RelabelAction::LabelKeep => write!(f, "labelkeep"),
+            RelabelAction::Lowercase => write!(f, "lowercase"),
+            RelabelAction::Uppercase => write!(f, "uppercase"),
+        }
+    }
+}
+
+/// A relabeling rule. Settings that are not set fall back to the defaults of Prometheus.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RelabelConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) source_labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) separator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) modulus: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) target_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) replacement: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) action: Option<RelabelAction>,
+}
+
+impl RelabelConfig {
+    /// Create a new relabeling rule with the given action and all other settings unset.
+    pub fn new(action: RelabelAction) -> Self {
+        RelabelConfig {
+            action: Some(action),
+            ..Default::default()
+        }
+    }
+
+    /// Set the source labels.
+    pub fn with_source_labels(mut self, labels: &[&str]) -> Self {
+        self.source_labels = labels.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    /// Set the separator of concatenated source label values.
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = Some(separator.to_string());
+        self
+    }
+
+    /// Set the regular expression.
+    pub fn with_regex(mut self, regex: &str) -> Self {
+        self.regex = Some(regex.to_string());
+        self
+    }
+
+    /// Set the modulus of the `hashmod` action.
+    pub fn with_modulus(mut self, modulus: u64) -> Self {
+        self.modulus = Some(modulus);
+        self
+    }
+
+    /// Set the target label.
+    pub fn with_target_label(mut self, label: &str) -> Self {
+        self.target_label = Some(label.to_string());
+        self
+    }
+
+    /// Set the replacement.
+    pub fn with_replacement(mut self, replacement: &str) -> Self {
+        self.replacement = Some(replacement.to_string());
+        self
+    }
+
+    /// Get the source labels.
+    pub fn source_labels(&self) -> &[String] {
+        &self.source_labels
+    }
+
+    /// Get the separator (defaults to `;`).
+    pub fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or(";")
+    }
+
+    /// Get the regular expression (defaults to `(.*)`).
+    pub fn regex(&self) -> &str {
+        self.regex.as_deref().unwrap_or("(.*)")
+    }
+
+    /// Get the modulus of the `hashmod` action.
+    pub fn modulus(&self) -> Option<u64> {
+        self.modulus
+    }
+
+    /// Get the target label.
+    pub fn target_label(&self) -> Option<&str> {
+        self.target_label.as_deref()
+    }
+
+    /// Get the replacement (defaults to `$1`).
+    pub fn replacement(&self) -> &str {
+        self.replacement.as_deref().unwrap_or("$1")
+    }
+
+    /// Get the action (defaults to [RelabelAction::Replace]).
+    pub fn action(&self) -> RelabelAction {
+        self.action.unwrap_or(RelabelAction::Replace)
+    }
+}
+
+/// The alerting configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertingConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) alert_relabel_configs: Vec<RelabelConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) alertmanagers: Vec<AlertmanagerConfig>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl AlertingConfig {
+    /// Get the relabeling rules that are applied to alerts before sending them.
+    pub fn alert_relabel_configs(&self) -> &[RelabelConfig] {
+        &self.alert_relabel_configs
+    }
+
+    /// Get the configurations of Alertmanagers alerts are sent to.
+    pub fn alertmanagers(&self) -> &[AlertmanagerConfig] {
+        &self.alertmanagers
+    }
+
+    /// Get all other alerting settings.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+}
+
+/// The configuration of a set of Alertmanagers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertmanagerConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scheme: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) path_prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) timeout: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) api_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) static_configs: Vec<StaticConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) relabel_configs: Vec<RelabelConfig>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl AlertmanagerConfig {
+    /// Get the URL scheme.
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.as_deref()
+    }
+
+    /// Get the path prefix of the API.
+    pub fn path_prefix(&self) -> Option<&str> {
+        self.path_prefix.as_deref()
+    }
+
+    /// Get the timeout of requests.
+    pub fn timeout(&self) -> Option<&str> {
+        self.timeout.as_deref()
+    }
+
+    /// Get the API version, e.g. `v2`.
+    pub fn api_version(&self) -> Option<&str> {
+        self.api_version.as_deref()
+    }
+
+    /// Get the statically configured Alertmanagers.
+    pub fn static_configs(&self) -> &[StaticConfig] {
+        &self.static_configs
+    }
+
+    /// Get the relabeling rules that are applied to discovered Alertmanagers.
+    pub fn relabel_configs(&self) -> &[RelabelConfig] {
+        &self.relabel_configs
+    }
+
+    /// Get all other settings, including service discovery configurations.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+}
+
+/// A remote write configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RemoteWriteConfig {
+    pub(crate) url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) remote_timeout: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) write_relabel_configs: Vec<RelabelConfig>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl RemoteWriteConfig {
+    /// Get the URL of the remote write endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the name of this remote write configuration.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the timeout of requests.
+    pub fn remote_timeout(&self) -> Option<&str> {
+        self.remote_timeout.as_deref()
+    }
+
+    /// Get the relabeling rules that are applied to series before sending them.
+    pub fn write_relabel_configs(&self) -> &[RelabelConfig] {
+        &self.write_relabel_configs
+    }
+
+    /// Get all other settings, e.g. `queue_config`.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+}
+
+/// A remote read configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RemoteReadConfig {
+    pub(crate) url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) remote_timeout: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) read_recent: Option<bool>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) required_matchers: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub(crate) other: Other,
+}
+
+impl RemoteReadConfig {
+    /// Get the URL of the remote read endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the name of this remote read configuration.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the timeout of requests.
+    pub fn remote_timeout(&self) -> Option<&str> {
+        self.remote_timeout.as_deref()
+    }
+
+    /// Check whether queries for recent data are sent to the remote endpoint.
+    pub fn read_recent(&self) -> Option<bool> {
+        self.read_recent
+    }
+
+    /// Get the equality matchers that must be present in a query in order to send it.
+    pub fn required_matchers(&self) -> &BTreeMap<String, String> {
+        &self.required_matchers
+    }
+
+    /// Get all other settings.
+    pub fn other(&self) -> &Other {
+        &self.other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Abbreviated output of a Prometheus server.
+    const CONFIG: &str = r#"global:
+  scrape_interval: 15s
+  scrape_timeout: 10s
+  evaluation_interval: 15s
+  external_labels:
+    cluster: eu-1
+alerting:
+  alertmanagers:
+  - follow_redirects: true
+    scheme: http
+    timeout: 10s
+    api_version: v2
+    static_configs:
+    - targets:
+      - alertmanager:9093
+rule_files:
+- /etc/prometheus/rules/*.yml
+scrape_configs:
+- job_name: kubernetes-pods
+  honor_timestamps: true
+  scrape_interval: 30s
+  metrics_path: /metrics
+  scheme: http
+  kubernetes_sd_configs:
+  - role: pod
+    kubeconfig_file: ""
+    follow_redirects: true
+  relabel_configs:
+  - source_labels: [__meta_kubernetes_pod_annotation_prometheus_io_scrape]
+    separator: ;
+    regex: "true"
+    replacement: $1
+    action: keep
+  - separator: ;
+    regex: __meta_kubernetes_pod_label_(.+)
+    replacement: $1
+    action: labelmap
+  metric_relabel_configs:
+  - source_labels: [__name__]
+    regex: go_.*
+    action: drop
+- job_name: consul
+  consul_sd_configs:
+  - server: localhost:8500
+remote_write:
+- url: http://mimir:9009/api/v1/push
+  remote_timeout: 30s
+  queue_config:
+    capacity: 10000
+storage:
+  tsdb:
+    out_of_order_time_window: 10m
+"#;
+
+    #[test]
+    fn test_config_deserialization() {
+        let config = PrometheusConfig::from_yaml(CONFIG).unwrap();
+
+        assert_eq!(config.global().scrape_interval(), Some("15s"));
+        assert_eq!(
+            config.global().external_labels().get("cluster").unwrap(),
+            "eu-1"
+        );
+        assert_eq!(config.rule_files(), &["/etc/prometheus/rules/*.yml"]);
+
+        let alerting = config.alerting().unwrap();
+        assert_eq!(alerting.alertmanagers()[0].api_version(), Some("v2"));
+        assert_eq!(
+            alerting.alertmanagers()[0].static_configs()[0].targets(),
+            &["alertmanager:9093"]
+        );
+
+        let pods = config.scrape_config("kubernetes-pods").unwrap();
+        assert_eq!(config.scrape_interval_of(pods), "30s");
+        assert_eq!(pods.kubernetes_sd_configs()[0].role(), "pod");
+        assert_eq!(pods.relabel_configs()[0].action(), RelabelAction::Keep);
+        assert_eq!(pods.relabel_configs()[1].action(), RelabelAction::LabelMap);
+        assert_eq!(pods.metric_relabel_configs()[0].replacement(), "$1");
+
+        let consul = config.scrape_config("consul").unwrap();
+        assert_eq!(config.scrape_interval_of(consul), "15s");
+        assert!(consul.sd_configs().contains_key("consul_sd_configs"));
+
+        assert_eq!(
+            config.remote_write()[0].url(),
+            "http://mimir:9009/api/v1/push"
+        );
+        assert!(config.remote_write()[0]
+            .other()
+            .contains_key("queue_config"));
+        assert!(config.other().contains_key("storage"));
+    }
+
+    #[test]
+    fn test_config_roundtrip() {
+        let config = PrometheusConfig::from_yaml(CONFIG).unwrap();
+
+        let yaml = config.to_yaml().unwrap();
+
+        assert_eq!(PrometheusConfig::from_yaml(&yaml).unwrap(), config);
+    }
+
+    #[test]
+    fn test_config_for_error() {
+        let result = PrometheusConfig::from_yaml(
+            "scrape_configs:\n- job_name: x\n  relabel_configs:\n  - action: rewrite\n",
+        );
+
+        assert!(result.is_err());
+
+        let result = PrometheusConfig::from_yaml(
+            "scrape_configs:\n- job_name: x\n  relabel_configs:\n  - action: keep\n",
+        );
+
+        assert!(result.is_ok());
+    }
+}
@@ -16,6 +16,23 @@ pub enum Error {
     UrlParse(url::ParseError),
     ResponseParse(serde_json::Error),
     MissingField,
+    AdminApiDisabled,
+    LifecycleApiDisabled,
+    ConfigReloadFailed(ConfigReloadFailed),
+    ReadinessTimeout,
+    Snappy(snap::Error),
+    ProtobufDecode(prost::DecodeError),
+    InvalidRemoteReadResponse(InvalidRemoteReadResponse),
+    InvalidExposition(InvalidExposition),
+    PushRejected(PushRejected),
+    AlertmanagerError(AlertmanagerError),
+    YamlParse(serde_yaml::Error),
+    UnsupportedFunction(UnsupportedFunction),
+    Csv(csv::Error),
+    Io(std::io::Error),
+    /// An error of the Arrow or Parquet libraries (see the `arrow` feature). This variant
+    /// exists regardless of the feature so that enabling it does not change this enum.
+    Arrow(Box<dyn StdError + Send + Sync>),
 }
 
 impl fmt::Display for Error {
@@ -32,6 +49,21 @@ impl fmt::Display for Error {
             Self::UrlParse(e) => e.fmt(f),
             Self::ResponseParse(e) => e.fmt(f),
             Self::MissingField => MissingFieldError.fmt(f),
+            Self::AdminApiDisabled => AdminApiDisabledError.fmt(f),
+            Self::LifecycleApiDisabled => LifecycleApiDisabledError.fmt(f),
+            Self::ConfigReloadFailed(e) => e.fmt(f),
+            Self::ReadinessTimeout => ReadinessTimeoutError.fmt(f),
+            Self::Snappy(e) => e.fmt(f),
+            Self::ProtobufDecode(e) => e.fmt(f),
+            Self::InvalidRemoteReadResponse(e) => e.fmt(f),
+            Self::InvalidExposition(e) => e.fmt(f),
+            Self::PushRejected(e) => e.fmt(f),
+            Self::AlertmanagerError(e) => e.fmt(f),
+            Self::YamlParse(e) => e.fmt(f),
+            Self::UnsupportedFunction(e) => e.fmt(f),
+            Self::Csv(e) => e.fmt(f),
+            Self::Io(e) => e.fmt(f),
+            Self::Arrow(e) => e.fmt(f),
         }
     }
 }
@@ -93,14 +125,14 @@ impl fmt::Display for ResponseError {
 
 /// This error is thrown when the JSON response's `data.resultType` field contains
 /// an unexpected result type.<br>
-/// For instant and range queries this is expected to be either `vector` or `matrix`.
+/// For instant and range queries this is expected to be one of `vector`, `matrix`, `scalar` or `string`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnsupportedQueryResultType(pub String);
 
 impl fmt::Display for UnsupportedQueryResultType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let UnsupportedQueryResultType(data_type) = self;
-        write!(f, "the API returned an unsupported result type, is '{}', must be either 'vector' or 'matrix'", data_type)
+        write!(f, "the API returned an unsupported result type, is '{}', must be one of 'vector', 'matrix', 'scalar' or 'string'", data_type)
     }
 }
 
@@ -141,3 +173,144 @@ impl fmt::Display for MissingFieldError {
         )
     }
 }
+
+/// This error is thrown when an endpoint of the TSDB admin API is called, but the
+/// Prometheus server was not started with `--web.enable-admin-api`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdminApiDisabledError;
+
+impl fmt::Display for AdminApiDisabledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the admin API is disabled, start Prometheus with '--web.enable-admin-api' to enable it"
+        )
+    }
+}
+
+/// This error is thrown when the configuration is to be reloaded or the server is to be
+/// shut down, but the Prometheus server was not started with `--web.enable-lifecycle`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LifecycleApiDisabledError;
+
+impl fmt::Display for LifecycleApiDisabledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the lifecycle API is disabled, start Prometheus with '--web.enable-lifecycle' to enable it"
+        )
+    }
+}
+
+/// This error is thrown when the Prometheus server failed to reload its configuration.<br>
+/// The reason as returned by the server is included in this error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigReloadFailed {
+    pub message: String,
+}
+
+impl fmt::Display for ConfigReloadFailed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the configuration reload failed: {}", self.message)
+    }
+}
+
+/// This error is thrown when the Prometheus server did not become ready to serve
+/// traffic within the given time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReadinessTimeoutError;
+
+impl fmt::Display for ReadinessTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the server did not become ready before the timeout elapsed"
+        )
+    }
+}
+
+/// This error is thrown when the response of a remote read endpoint cannot be decoded,
+/// e.g. because a frame is corrupted or a chunk uses an unsupported encoding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidRemoteReadResponse {
+    pub message: String,
+}
+
+impl fmt::Display for InvalidRemoteReadResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the remote read response is invalid: {}", self.message)
+    }
+}
+
+/// This error is thrown when a metrics exposition cannot be parsed because
+/// a line does not comply with the exposition format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidExposition {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for InvalidExposition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the metrics exposition is invalid in line {}: {}",
+            self.line, self.message
+        )
+    }
+}
+
+/// This error is thrown when the Pushgateway rejects a push or deletion,
+/// e.g. because the pushed metrics are inconsistent with those of other groups.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushRejected {
+    pub status: u16,
+    pub message: String,
+}
+
+impl fmt::Display for PushRejected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the push was rejected with status {}: {}",
+            self.status, self.message
+        )
+    }
+}
+
+/// This error is thrown when the Alertmanager API responds with an error,
+/// e.g. because a silence does not exist or is invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertmanagerError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl fmt::Display for AlertmanagerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the Alertmanager responded with status {}: {}",
+            self.status, self.message
+        )
+    }
+}
+
+/// This error is thrown when an expression uses a PromQL function that the Prometheus server
+/// does not support (see [crate::Capabilities]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsupportedFunction {
+    pub function: String,
+    pub required: String,
+    pub version: String,
+}
+
+impl fmt::Display for UnsupportedFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the function {} requires Prometheus {}, but the server runs {}",
+            self.function, self.required, self.version
+        )
+    }
+}
@@ -0,0 +1,488 @@
+//! Write query results as CSV or [JSON Lines](https://jsonlines.org), e.g. for quick reports
+//! and spreadsheets.
+//!
+//! Both exporters write to any [std::io::Write] one sample (or series) at a time, so large
+//! results are never formatted in memory as a whole.
+//!
+//! ```rust
+//! use prometheus_http_query::export::{CsvExporter, JsonLinesExporter, TimestampFormat};
+//! use prometheus_http_query::{Client, Error};
+//! use std::fs::File;
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let client = Client::default();
+//!
+//!     let result = client.query_range("up", 1623345960, 1623346260, Some("30s"), None).await?;
+//!
+//!     // instance,Job,time,value
+//!     // localhost:9090,prometheus,2021-06-10T17:26:00Z,1
+//!     let exporter = CsvExporter::new()
+//!         .column("instance", "instance")
+//!         .column("job", "Job")
+//!         .timestamp_column("time")
+//!         .timestamp_format(TimestampFormat::Rfc3339);
+//!
+//!     exporter.write(File::create(std::env::temp_dir().join("up.csv")).unwrap(), &result)?;
+//!
+//!     // {"metric":{"__name__":"up","instance":"localhost:9090","job":"prometheus"},"values":[[1623345960,"1"],...]}
+//!     let exporter = JsonLinesExporter::new().per_series();
+//!
+//!     exporter.write(File::create(std::env::temp_dir().join("up.jsonl")).unwrap(), &result)?;
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::error::{Error, InvalidFunctionArgument};
+use crate::response::{
+    millis, ser, FloatSeries, InstantVector, QueryResultType, RangeVector, Sample,
+};
+use serde::ser::{SerializeMap, SerializeSeq, SerializeTuple};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// The format of exported timestamps.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum TimestampFormat {
+    /// Seconds since the epoch with millisecond precision as returned by the API,
+    /// e.g. `1435781451.781`.
+    #[default]
+    Seconds,
+    /// Milliseconds since the epoch, e.g. `1435781451781`.
+    Milliseconds,
+    /// An RFC 3339 timestamp in UTC, e.g. `2015-07-01T20:10:51.781Z`.
+    Rfc3339,
+}
+
+impl TimestampFormat {
+    fn format(&self, timestamp: f64) -> Result<String, Error> {
+        match self {
+            TimestampFormat::Seconds => Ok(timestamp.to_string()),
+            TimestampFormat::Milliseconds => Ok(millis(timestamp).to_string()),
+            TimestampFormat::Rfc3339 => rfc3339(timestamp),
+        }
+    }
+}
+
+fn rfc3339(timestamp: f64) -> Result<String, Error> {
+    let invalid = || {
+        Error::InvalidFunctionArgument(InvalidFunctionArgument {
+            message: format!("the timestamp {} cannot be formatted", timestamp),
+        })
+    };
+
+    OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis(timestamp)) * 1_000_000)
+        .map_err(|_| invalid())?
+        .format(&Rfc3339)
+        .map_err(|_| invalid())
+}
+
+fn series(result: &QueryResultType) -> Result<FloatSeries<'_>, Error> {
+    result.float_series().ok_or_else(|| {
+        Error::InvalidFunctionArgument(InvalidFunctionArgument {
+            message: String::from("results of type 'string' cannot be exported"),
+        })
+    })
+}
+
+fn instant_series(vectors: &[InstantVector]) -> FloatSeries<'_> {
+    vectors
+        .iter()
+        .filter_map(|v| Some((v.metric(), std::slice::from_ref(v.sample()?))))
+        .collect()
+}
+
+fn range_series(vectors: &[RangeVector]) -> FloatSeries<'_> {
+    vectors.iter().map(|v| (v.metric(), v.samples())).collect()
+}
+
+/// Writes query results as CSV with one row per sample, e.g.:
+///
+/// ```text
+/// __name__,instance,job,timestamp,value
+/// up,localhost:9090,prometheus,1435781451.781,1
+/// ```
+///
+/// Values are formatted just like Prometheus does, i.e. `NaN`, `+Inf` and `-Inf` for special
+/// values. Only float samples are exported, native histogram samples are skipped.
+#[derive(Debug, Clone)]
+pub struct CsvExporter {
+    pub(crate) columns: Vec<(String, String)>,
+    pub(crate) timestamp_column: String,
+    pub(crate) value_column: String,
+    pub(crate) timestamp_format: TimestampFormat,
+}
+
+impl Default for CsvExporter {
+    fn default() -> Self {
+        CsvExporter {
+            columns: vec![],
+            timestamp_column: String::from("timestamp"),
+            value_column: String::from("value"),
+            timestamp_format: TimestampFormat::default(),
+        }
+    }
+}
+
+impl CsvExporter {
+    /// Create a new exporter that writes one column per label name (sorted by name),
+    /// followed by the timestamp in seconds and the value.
+    pub fn new() -> Self {
+        CsvExporter::default()
+    }
+
+    /// Add a column with the given header that holds the values of a label. Once a column
+    /// is added, only the configured labels are exported in the order they were added.
+    pub fn column(mut self, label: &str, header: &str) -> Self {
+        self.columns.push((label.to_string(), header.to_string()));
+        self
+    }
+
+    /// Set the header of the timestamp column (default: `timestamp`).
+    pub fn timestamp_column(mut self, header: &str) -> Self {
+        self.timestamp_column = header.to_string();
+        self
+    }
+
+    /// Set the header of the value column (default: `value`).
+    pub fn value_column(mut self, header: &str) -> Self {
+        self.value_column = header.to_string();
+        self
+    }
+
+    /// Set the format of the timestamp column.
+    pub fn timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+
+    /// Write an instant vector, range vector or scalar result.
+    pub fn write<W: Write>(&self, writer: W, result: &QueryResultType) -> Result<(), Error> {
+        self.write_series(writer, &series(result)?)
+    }
+
+    /// Write a set of instant vectors, e.g. the result of [crate::Client::query].
+    pub fn write_instant<W: Write>(
+        &self,
+        writer: W,
+        vectors: &[InstantVector],
+    ) -> Result<(), Error> {
+        self.write_series(writer, &instant_series(vectors))
+    }
+
+    /// Write a set of range vectors, e.g. the result of [crate::Client::query_range].
+    pub fn write_range<W: Write>(&self, writer: W, vectors: &[RangeVector]) -> Result<(), Error> {
+        self.write_series(writer, &range_series(vectors))
+    }
+
+    fn write_series<W: Write>(&self, writer: W, series: &FloatSeries) -> Result<(), Error> {
+        let columns: Vec<(&str, &str)> = if self.columns.is_empty() {
+            series
+                .iter()
+                .flat_map(|(metric, _)| metric.keys().map(|k| k.as_str()))
+                .collect::<BTreeSet<&str>>()
+                .into_iter()
+                .map(|label| (label, label))
+                .collect()
+        } else {
+            self.columns
+                .iter()
+                .map(|(label, header)| (label.as_str(), header.as_str()))
+                .collect()
+        };
+
+        let mut writer = csv::Writer::from_writer(writer);
+
+        let header = columns
+            .iter()
+            .map(|(_, header)| *header)
+            .chain([self.timestamp_column.as_str(), self.value_column.as_str()]);
+
+        writer.write_record(header).map_err(Error::Csv)?;
+
+        for (metric, samples) in series {
+            for sample in samples.iter() {
+                for (label, _) in &columns {
+                    let value = metric.get(*label).map(|v| v.as_str()).unwrap_or_default();
+                    writer.write_field(value).map_err(Error::Csv)?;
+                }
+                writer
+                    .write_field(self.timestamp_format.format(sample.timestamp())?)
+                    .map_err(Error::Csv)?;
+                writer
+                    .write_field(ser::format_f64(sample.value()))
+                    .map_err(Error::Csv)?;
+                writer.write_record(None::<&[u8]>).map_err(Error::Csv)?;
+            }
+        }
+
+        writer.flush().map_err(Error::Io)
+    }
+}
+
+/// Writes query results as [JSON Lines](https://jsonlines.org), either with one sample per line:
+///
+/// ```text
+/// {"metric":{"__name__":"up","job":"prometheus"},"timestamp":1435781451.781,"value":"1"}
+/// ```
+///
+/// or with one series per line, just like the API returns them:
+///
+/// ```text
+/// {"metric":{"__name__":"up","job":"prometheus"},"values":[[1435781451.781,"1"],[1435781466.781,"1"]]}
+/// ```
+///
+/// Values are encoded as strings like in API responses, as JSON cannot represent `NaN` or
+/// infinite numbers. Only float samples are exported, native histogram samples are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct JsonLinesExporter {
+    pub(crate) per_series: bool,
+    pub(crate) timestamp_format: TimestampFormat,
+}
+
+impl JsonLinesExporter {
+    /// Create a new exporter that writes one sample per line with timestamps in seconds.
+    pub fn new() -> Self {
+        JsonLinesExporter::default()
+    }
+
+    /// Write one series per line instead of one sample per line.
+    pub fn per_series(mut self) -> Self {
+        self.per_series = true;
+        self
+    }
+
+    /// Set the format of the timestamps.
+    pub fn timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+
+    /// Write an instant vector, range vector or scalar result.
+    pub fn write<W: Write>(&self, writer: W, result: &QueryResultType) -> Result<(), Error> {
+        self.write_series(writer, &series(result)?)
+    }
+
+    /// Write a set of instant vectors, e.g. the result of [crate::Client::query].
+    pub fn write_instant<W: Write>(
+        &self,
+        writer: W,
+        vectors: &[InstantVector],
+    ) -> Result<(), Error> {
+        self.write_series(writer, &instant_series(vectors))
+    }
+
+    /// Write a set of range vectors, e.g. the result of [crate::Client::query_range].
+    pub fn write_range<W: Write>(&self, writer: W, vectors: &[RangeVector]) -> Result<(), Error> {
+        self.write_series(writer, &range_series(vectors))
+    }
+
+    fn write_series<W: Write>(&self, mut writer: W, series: &FloatSeries) -> Result<(), Error> {
+        let format = self.timestamp_format;
+
+        for (metric, samples) in series {
+            if self.per_series {
+                let line = SeriesLine {
+                    metric: Labels(metric),
+                    values: Samples(samples, format),
+                };
+                write_line(&mut writer, &line)?;
+            } else {
+                for sample in samples.iter() {
+                    let line = SampleLine {
+                        metric: Labels(metric),
+                        timestamp: Timestamp(sample.timestamp(), format),
+                        value: ser::format_f64(sample.value()),
+                    };
+                    write_line(&mut writer, &line)?;
+                }
+            }
+        }
+
+        writer.flush().map_err(Error::Io)
+    }
+}
+
+fn write_line<W: Write, T: Serialize>(writer: &mut W, line: &T) -> Result<(), Error> {
+    serde_json::to_writer(&mut *writer, line).map_err(|e| Error::Io(e.into()))?;
+    writer.write_all(b"\n").map_err(Error::Io)
+}
+
+#[derive(Serialize)]
+struct SampleLine<'a> {
+    metric: Labels<'a>,
+    timestamp: Timestamp,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct SeriesLine<'a> {
+    metric: Labels<'a>,
+    values: Samples<'a>,
+}
+
+// Labels are sorted by name so that the output is deterministic.
+struct Labels<'a>(&'a HashMap<String, String>);
+
+impl Serialize for Labels<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut labels: Vec<(&String, &String)> = self.0.iter().collect();
+        labels.sort();
+
+        let mut map = serializer.serialize_map(Some(labels.len()))?;
+        for (name, value) in labels {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+struct Timestamp(f64, TimestampFormat);
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.1 {
+            TimestampFormat::Seconds => ser::serialize_timestamp(&self.0, serializer),
+            TimestampFormat::Milliseconds => serializer.serialize_i64(millis(self.0)),
+            TimestampFormat::Rfc3339 => {
+                let raw = rfc3339(self.0).map_err(serde::ser::Error::custom)?;
+                serializer.serialize_str(&raw)
+            }
+        }
+    }
+}
+
+// Samples are encoded as `[<timestamp>, "<value>"]` like in API responses.
+struct Samples<'a>(&'a [Sample], TimestampFormat);
+
+impl Serialize for Samples<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Pair<'a>(&'a Sample, TimestampFormat);
+
+        impl Serialize for Pair<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut tuple = serializer.serialize_tuple(2)?;
+                tuple.serialize_element(&Timestamp(self.0.timestamp(), self.1))?;
+                tuple.serialize_element(&ser::format_f64(self.0.value()))?;
+                tuple.end()
+            }
+        }
+
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for sample in self.0 {
+            seq.serialize_element(&Pair(sample, self.1))?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    // Adds a series that needs to be quoted in CSV and has a special value.
+    fn matrix() -> Vec<RangeVector> {
+        let mut matrix = fixtures::matrix();
+        matrix.push(RangeVector::new(
+            HashMap::from([("job".to_string(), "blackbox, \"main\"".to_string())]),
+            vec![Sample::new(1435781451.781, f64::NAN)],
+        ));
+        matrix
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut output = vec![];
+
+        CsvExporter::new()
+            .write_range(&mut output, &matrix())
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "__name__,instance,job,timestamp,value\n\
+             up,a:9100,node,1435781430,1\n\
+             up,a:9100,node,1435781445,0\n\
+             up,a:9100,node,1435781475,1\n\
+             up,,prometheus,1435781445,1\n\
+             ,,\"blackbox, \"\"main\"\"\",1435781451.781,NaN\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_with_columns() {
+        let mut output = vec![];
+
+        CsvExporter::new()
+            .column("job", "Job")
+            .timestamp_column("time")
+            .timestamp_format(TimestampFormat::Rfc3339)
+            .write(&mut output, &QueryResultType::Matrix(matrix()))
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Job,time,value\n\
+             node,2015-07-01T20:10:30Z,1\n\
+             node,2015-07-01T20:10:45Z,0\n\
+             node,2015-07-01T20:11:15Z,1\n\
+             prometheus,2015-07-01T20:10:45Z,1\n\
+             \"blackbox, \"\"main\"\"\",2015-07-01T20:10:51.781Z,NaN\n"
+        );
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let mut output = vec![];
+
+        JsonLinesExporter::new()
+            .timestamp_format(TimestampFormat::Milliseconds)
+            .write_range(&mut output, &matrix()[2..])
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"metric\":{\"job\":\"blackbox, \\\"main\\\"\"},\"timestamp\":1435781451781,\"value\":\"NaN\"}\n"
+        );
+
+        let mut output = vec![];
+
+        JsonLinesExporter::new()
+            .write_range(&mut output, &matrix()[1..2])
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"metric\":{\"__name__\":\"up\",\"job\":\"prometheus\"},\"timestamp\":1435781445,\"value\":\"1\"}\n"
+        );
+
+        let mut output = vec![];
+
+        JsonLinesExporter::new()
+            .per_series()
+            .write_range(&mut output, &matrix())
+            .unwrap();
+
+        let lines: Vec<RangeVector> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], matrix()[1]);
+        assert!(lines[2].samples()[0].value().is_nan());
+    }
+
+    #[test]
+    fn test_export_for_error() {
+        let result = QueryResultType::String(crate::response::StringSample::new(1.0, "foo"));
+
+        assert!(CsvExporter::new().write(vec![], &result).is_err());
+        assert!(JsonLinesExporter::new().write(vec![], &result).is_err());
+    }
+}
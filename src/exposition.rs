@@ -0,0 +1,995 @@
+//! A parser and encoder for the Prometheus [text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/#text-based-format)
+//! and the [OpenMetrics](https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md)
+//! text format, i.e. the formats in which targets expose their metrics (see [crate::response::ActiveTarget::scrape_url])
+//! and in which the `/federate` endpoint returns time series (see [crate::Client::federate]).
+//! OpenMetrics is also the input format of `promtool tsdb create-blocks-from openmetrics`.
+//!
+//! ```rust
+//! use prometheus_http_query::exposition::{self, MetricFamily, MetricSample};
+//! use prometheus_http_query::response::MetricType;
+//!
+//! let input = r#"# HELP http_requests_total The total number of HTTP requests.
+//! ## TYPE http_requests_total counter
+//! http_requests_total{method="post",code="200"} 1027 1395066363000
+//! http_requests_total{method="post",code="400"} 3 1395066363000
+//! "#;
+//!
+//! let families = exposition::parse_text(input).unwrap();
+//!
+//! assert_eq!(families[0].metric_type(), MetricType::Counter);
+//! assert_eq!(families[0].samples()[0].value(), 1027.0);
+//!
+//! let family = MetricFamily::new("temperature_celsius", MetricType::Gauge)
+//!     .with_help("The current temperature.")
+//!     .with_sample(MetricSample::new("temperature_celsius", 21.5).with_label("room", "kitchen"));
+//!
+//! let encoded = exposition::encode_text(&[family]);
+//!
+//! assert!(encoded.ends_with("temperature_celsius{room=\"kitchen\"} 21.5\n"));
+//! ```
+use crate::error::{Error, InvalidExposition};
+use crate::response::{self, ser, MetricType};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// A metric family, i.e. a group of samples that share a metric name, type and help text.
+///
+/// The samples of histograms and summaries keep their full names, e.g. `_bucket`, `_sum` and
+/// `_count` samples of a histogram are contained in the family of the histogram itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricFamily {
+    pub(crate) name: String,
+    pub(crate) help: Option<String>,
+    pub(crate) unit: Option<String>,
+    pub(crate) metric_type: MetricType,
+    pub(crate) samples: Vec<MetricSample>,
+}
+
+impl MetricFamily {
+    /// Create a new metric family without any samples.
+    pub fn new(name: &str, metric_type: MetricType) -> Self {
+        MetricFamily {
+            name: name.to_string(),
+            help: None,
+            unit: None,
+            metric_type,
+            samples: vec![],
+        }
+    }
+
+    /// Set the help text of this metric family.
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    /// Set the unit of this metric family.
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_string());
+        self
+    }
+
+    /// Add a sample to this metric family.
+    pub fn with_sample(mut self, sample: MetricSample) -> Self {
+        self.samples.push(sample);
+        self
+    }
+
+    /// Get the name of this metric family.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the help text of this metric family.
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    /// Get the unit of this metric family.
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    /// Get the type of this metric family. Untyped metrics are of type [MetricType::Unknown].
+    pub fn metric_type(&self) -> MetricType {
+        self.metric_type
+    }
+
+    /// Get the samples of this metric family.
+    pub fn samples(&self) -> &[MetricSample] {
+        &self.samples
+    }
+}
+
+/// A single sample of a metric family.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    pub(crate) name: String,
+    pub(crate) labels: BTreeMap<String, String>,
+    pub(crate) value: f64,
+    pub(crate) timestamp: Option<i64>,
+    pub(crate) exemplar: Option<Exemplar>,
+}
+
+impl MetricSample {
+    /// Create a new sample without labels and timestamp.
+    pub fn new(name: &str, value: f64) -> Self {
+        MetricSample {
+            name: name.to_string(),
+            labels: BTreeMap::new(),
+            value,
+            timestamp: None,
+            exemplar: None,
+        }
+    }
+
+    /// Add a label to this sample.
+    pub fn with_label(mut self, name: &str, value: &str) -> Self {
+        self.labels.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Set the timestamp of this sample (Unix timestamp in milliseconds).
+    pub fn with_timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Get the full name of this sample, e.g. `http_request_duration_seconds_bucket`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the labels of this sample.
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    /// Get the value of this sample.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the timestamp of this sample (Unix timestamp in milliseconds), if exposed.
+    pub fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    /// Attach an exemplar to this sample (OpenMetrics only).
+    pub fn with_exemplar(mut self, exemplar: Exemplar) -> Self {
+        self.exemplar = Some(exemplar);
+        self
+    }
+
+    /// Get the exemplar of this sample, if exposed.
+    pub fn exemplar(&self) -> Option<&Exemplar> {
+        self.exemplar.as_ref()
+    }
+}
+
+/// An exemplar that is attached to a sample in the OpenMetrics format, e.g. to refer to a trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exemplar {
+    pub(crate) labels: BTreeMap<String, String>,
+    pub(crate) value: f64,
+    pub(crate) timestamp: Option<i64>,
+}
+
+impl Exemplar {
+    /// Create a new exemplar without labels and timestamp.
+    pub fn new(value: f64) -> Self {
+        Exemplar {
+            labels: BTreeMap::new(),
+            value,
+            timestamp: None,
+        }
+    }
+
+    /// Add a label to this exemplar.
+    pub fn with_label(mut self, name: &str, value: &str) -> Self {
+        self.labels.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Set the timestamp of this exemplar (Unix timestamp in milliseconds).
+    pub fn with_timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Get the labels of this exemplar.
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    /// Get the value of this exemplar.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the timestamp of this exemplar (Unix timestamp in milliseconds), if exposed.
+    pub fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+}
+
+/// Parse metric families from the Prometheus text exposition format (version 0.0.4).
+///
+/// Samples without a preceding `# TYPE` line are put into a metric family of type
+/// [MetricType::Unknown] that is named after the sample.
+pub fn parse_text(input: &str) -> Result<Vec<MetricFamily>, Error> {
+    parse(input, Format::Text)
+}
+
+/// Parse metric families from the [OpenMetrics](https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md)
+/// text format (version 1.0.0), including units, exemplars and `_created` series.
+///
+/// The input must be terminated by `# EOF`. Timestamps are converted from seconds
+/// to milliseconds.
+pub fn parse_openmetrics(input: &str) -> Result<Vec<MetricFamily>, Error> {
+    parse(input, Format::OpenMetrics)
+}
+
+/// Encode metric families in the Prometheus text exposition format (version 0.0.4).
+///
+/// This format does not know the OpenMetrics types [MetricType::Info] and [MetricType::Stateset],
+/// which are encoded as `gauge`, nor [MetricType::GaugeHistogram], which is encoded as `untyped`.
+/// Units and exemplars are omitted.
+pub fn encode_text(families: &[MetricFamily]) -> String {
+    encode(families, Format::Text)
+}
+
+/// Encode metric families in the OpenMetrics text format (version 1.0.0), terminated by `# EOF`.
+///
+/// The output can be used to backfill data with `promtool tsdb create-blocks-from openmetrics`.
+/// Note that OpenMetrics requires the samples of a counter to carry the suffix `_total`, while
+/// the metric family itself is named without it.
+pub fn encode_openmetrics(families: &[MetricFamily]) -> String {
+    encode(families, Format::OpenMetrics)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Format {
+    Text,
+    OpenMetrics,
+}
+
+fn parse(input: &str, format: Format) -> Result<Vec<MetricFamily>, Error> {
+    let mut families: Vec<MetricFamily> = vec![];
+    // Whether the last metric family was declared by a TYPE line.
+    let mut typed = false;
+    let mut eof = false;
+
+    for (index, line) in input.lines().enumerate() {
+        let invalid = |message: &str| {
+            Error::InvalidExposition(InvalidExposition {
+                line: index + 1,
+                message: message.to_string(),
+            })
+        };
+
+        if eof {
+            return Err(invalid("unexpected content after # EOF"));
+        }
+
+        let line = line.trim_matches(|c| c == ' ' || c == '\t');
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut cursor = Cursor::new(comment);
+            cursor.skip_whitespace();
+            let keyword = cursor.token();
+
+            match (keyword, format) {
+                ("HELP", _) | ("TYPE", _) | ("UNIT", Format::OpenMetrics) => (),
+                ("EOF", Format::OpenMetrics) if cursor.rest().is_empty() => {
+                    eof = true;
+                    continue;
+                }
+                (_, Format::Text) => continue,
+                (_, Format::OpenMetrics) => return Err(invalid("invalid comment")),
+            }
+
+            cursor.skip_whitespace();
+            let name = cursor
+                .metric_name()
+                .ok_or_else(|| invalid("invalid metric name"))?;
+            let rest = cursor.rest();
+            let rest = rest.strip_prefix([' ', '\t']).unwrap_or(rest);
+
+            let current = match families.last_mut() {
+                Some(f) if f.name == name && f.samples.is_empty() => f,
+                _ => {
+                    typed = false;
+                    families.push(MetricFamily::new(name, MetricType::Unknown));
+                    families.last_mut().unwrap()
+                }
+            };
+
+            match keyword {
+                "HELP" => {
+                    if current.help.is_some() {
+                        return Err(invalid(&format!("second HELP line for metric {}", name)));
+                    }
+                    current.help = Some(
+                        unescape(rest, format == Format::OpenMetrics)
+                            .ok_or_else(|| invalid("invalid escape sequence in help text"))?,
+                    );
+                }
+                "UNIT" => {
+                    if current.unit.is_some() {
+                        return Err(invalid(&format!("second UNIT line for metric {}", name)));
+                    }
+                    if !rest.is_empty() && !name.ends_with(&format!("_{}", rest)) {
+                        return Err(invalid(&format!(
+                            "unit {} is not a suffix of metric {}",
+                            rest, name
+                        )));
+                    }
+                    current.unit = Some(rest.to_string());
+                }
+                _ => {
+                    if typed {
+                        return Err(invalid(&format!("second TYPE line for metric {}", name)));
+                    }
+                    current.metric_type = match (rest.trim_end(), format) {
+                        ("counter", _) => MetricType::Counter,
+                        ("gauge", _) => MetricType::Gauge,
+                        ("histogram", _) => MetricType::Histogram,
+                        ("summary", _) => MetricType::Summary,
+                        ("untyped", Format::Text) => MetricType::Unknown,
+                        ("unknown", Format::OpenMetrics) => MetricType::Unknown,
+                        ("gaugehistogram", Format::OpenMetrics) => MetricType::GaugeHistogram,
+                        ("info", Format::OpenMetrics) => MetricType::Info,
+                        ("stateset", Format::OpenMetrics) => MetricType::Stateset,
+                        (other, _) => {
+                            return Err(invalid(&format!("unknown metric type {}", other)))
+                        }
+                    };
+                    typed = true;
+                }
+            }
+
+            continue;
+        }
+
+        let sample = parse_sample(line, format).map_err(|m| invalid(&m))?;
+
+        match families.last_mut() {
+            Some(f) if belongs_to(&sample.name, f) => f.samples.push(sample),
+            _ => {
+                typed = false;
+                let mut family = MetricFamily::new(&sample.name, MetricType::Unknown);
+                family.samples.push(sample);
+                families.push(family);
+            }
+        }
+    }
+
+    if format == Format::OpenMetrics && !eof {
+        return Err(Error::InvalidExposition(InvalidExposition {
+            line: input.lines().count(),
+            message: String::from("missing # EOF"),
+        }));
+    }
+
+    Ok(families)
+}
+
+fn encode(families: &[MetricFamily], format: Format) -> String {
+    let mut output = String::new();
+
+    for family in families {
+        let quotes = format == Format::OpenMetrics;
+
+        if let Some(help) = &family.help {
+            let _ = writeln!(output, "# HELP {} {}", family.name, escape(help, quotes));
+        }
+
+        let metric_type = match (family.metric_type, format) {
+            (MetricType::Counter, _) => "counter",
+            (MetricType::Gauge, _) => "gauge",
+            (MetricType::Histogram, _) => "histogram",
+            (MetricType::Summary, _) => "summary",
+            (MetricType::Info | MetricType::Stateset, Format::Text) => "gauge",
+            (MetricType::GaugeHistogram | MetricType::Unknown, Format::Text) => "untyped",
+            (MetricType::GaugeHistogram, Format::OpenMetrics) => "gaugehistogram",
+            (MetricType::Info, Format::OpenMetrics) => "info",
+            (MetricType::Stateset, Format::OpenMetrics) => "stateset",
+            (MetricType::Unknown, Format::OpenMetrics) => "unknown",
+        };
+
+        let _ = writeln!(output, "# TYPE {} {}", family.name, metric_type);
+
+        if let (Some(unit), Format::OpenMetrics) = (&family.unit, format) {
+            let _ = writeln!(output, "# UNIT {} {}", family.name, unit);
+        }
+
+        for sample in &family.samples {
+            output.push_str(&sample.name);
+            encode_labels(&mut output, &sample.labels);
+            output.push(' ');
+            output.push_str(&ser::format_f64(sample.value));
+            if let Some(t) = sample.timestamp {
+                output.push(' ');
+                output.push_str(&format_timestamp(t, format));
+            }
+            if let (Some(exemplar), Format::OpenMetrics) = (&sample.exemplar, format) {
+                output.push_str(" # ");
+                if exemplar.labels.is_empty() {
+                    output.push_str("{}");
+                }
+                encode_labels(&mut output, &exemplar.labels);
+                output.push(' ');
+                output.push_str(&ser::format_f64(exemplar.value));
+                if let Some(t) = exemplar.timestamp {
+                    output.push(' ');
+                    output.push_str(&format_timestamp(t, format));
+                }
+            }
+            output.push('\n');
+        }
+    }
+
+    if format == Format::OpenMetrics {
+        output.push_str("# EOF\n");
+    }
+
+    output
+}
+
+// Check whether a sample is part of a metric family, which includes the additional
+// series that are exposed for counters, histograms, summaries and info metrics.
+fn belongs_to(sample_name: &str, family: &MetricFamily) -> bool {
+    let suffixes: &[&str] = match family.metric_type {
+        MetricType::Counter => &["_total", "_created"],
+        MetricType::Histogram => &["_bucket", "_sum", "_count", "_created"],
+        MetricType::GaugeHistogram => &["_bucket", "_gsum", "_gcount"],
+        MetricType::Summary => &["_sum", "_count", "_created"],
+        MetricType::Info => &["_info"],
+        _ => &[],
+    };
+
+    match sample_name.strip_prefix(family.name.as_str()) {
+        Some("") => true,
+        Some(suffix) => suffixes.contains(&suffix),
+        None => false,
+    }
+}
+
+// Parse a sample line: a metric name, optional labels, the value, an optional timestamp
+// and (in OpenMetrics only) an optional exemplar.
+fn parse_sample(line: &str, format: Format) -> Result<MetricSample, String> {
+    let mut cursor = Cursor::new(line);
+
+    let name = cursor
+        .metric_name()
+        .ok_or_else(|| String::from("invalid metric name"))?;
+
+    let mut sample = MetricSample::new(name, 0.0);
+
+    let mut separated = cursor.skip_whitespace();
+
+    if cursor.eat('{') {
+        sample.labels = parse_labels(&mut cursor)?;
+        separated = cursor.skip_whitespace();
+    }
+
+    if !separated {
+        return Err(String::from(
+            "expected whitespace after the metric name or labels",
+        ));
+    }
+
+    let value = cursor.token();
+    sample.value = parse_value(value).ok_or_else(|| format!("invalid value {}", value))?;
+
+    cursor.skip_whitespace();
+
+    if !cursor.rest().starts_with('#') {
+        let timestamp = cursor.token();
+
+        if !timestamp.is_empty() {
+            sample.timestamp = Some(
+                parse_timestamp(timestamp, format)
+                    .ok_or_else(|| format!("invalid timestamp {}", timestamp))?,
+            );
+        }
+
+        cursor.skip_whitespace();
+    }
+
+    if format == Format::OpenMetrics && cursor.eat('#') {
+        cursor.skip_whitespace();
+
+        if !cursor.eat('{') {
+            return Err(String::from("expected labels of the exemplar"));
+        }
+
+        let mut exemplar = Exemplar::new(0.0);
+        exemplar.labels = parse_labels(&mut cursor)?;
+
+        cursor.skip_whitespace();
+
+        let value = cursor.token();
+        exemplar.value =
+            parse_value(value).ok_or_else(|| format!("invalid value {} of the exemplar", value))?;
+
+        cursor.skip_whitespace();
+
+        let timestamp = cursor.token();
+
+        if !timestamp.is_empty() {
+            exemplar.timestamp = Some(
+                parse_timestamp(timestamp, format)
+                    .ok_or_else(|| format!("invalid timestamp {} of the exemplar", timestamp))?,
+            );
+        }
+
+        cursor.skip_whitespace();
+
+        sample.exemplar = Some(exemplar);
+    }
+
+    if !cursor.rest().is_empty() {
+        return Err(format!("unexpected trailing content {}", cursor.rest()));
+    }
+
+    Ok(sample)
+}
+
+// Timestamps are given in milliseconds in the text format and in (fractional) seconds in OpenMetrics.
+fn parse_timestamp(timestamp: &str, format: Format) -> Option<i64> {
+    match format {
+        Format::Text => timestamp.parse().ok(),
+        Format::OpenMetrics => parse_value(timestamp)
+            .filter(|t| t.is_finite())
+            .map(response::millis),
+    }
+}
+
+fn format_timestamp(timestamp: i64, format: Format) -> String {
+    match format {
+        Format::Text => timestamp.to_string(),
+        Format::OpenMetrics => (timestamp as f64 / 1000.0).to_string(),
+    }
+}
+
+// Parse a set of labels after the opening curly brace up to and including the closing one.
+fn parse_labels(cursor: &mut Cursor) -> Result<BTreeMap<String, String>, String> {
+    let mut labels = BTreeMap::new();
+
+    loop {
+        cursor.skip_whitespace();
+
+        if cursor.eat('}') {
+            return Ok(labels);
+        }
+
+        let name = cursor
+            .label_name()
+            .ok_or_else(|| String::from("invalid label name"))?;
+
+        cursor.skip_whitespace();
+
+        if !cursor.eat('=') {
+            return Err(format!("expected '=' after label name {}", name));
+        }
+
+        cursor.skip_whitespace();
+
+        let value = cursor
+            .quoted()
+            .ok_or_else(|| format!("invalid value of label {}", name))?;
+
+        if labels.insert(name.to_string(), value).is_some() {
+            return Err(format!("duplicate label name {}", name));
+        }
+
+        cursor.skip_whitespace();
+
+        if !cursor.eat(',') && !cursor.rest().starts_with('}') {
+            return Err(String::from("expected ',' or '}' after a label"));
+        }
+    }
+}
+
+pub(crate) fn parse_value(value: &str) -> Option<f64> {
+    match value {
+        "+Inf" | "Inf" => Some(f64::INFINITY),
+        "-Inf" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        _ => value.parse().ok(),
+    }
+}
+
+pub(crate) fn encode_labels(output: &mut String, labels: &BTreeMap<String, String>) {
+    if labels.is_empty() {
+        return;
+    }
+
+    output.push('{');
+
+    for (i, (name, value)) in labels.iter().enumerate() {
+        if i > 0 {
+            output.push(',');
+        }
+        let _ = write!(output, "{}=\"{}\"", name, escape(value, true));
+    }
+
+    output.push('}');
+}
+
+// Escape backslashes and line feeds (and double quotes in label values).
+pub(crate) fn escape(text: &str, quotes: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' if quotes => escaped.push_str("\\\""),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+// Reverse `escape`. Returns None if the text contains an invalid escape sequence.
+pub(crate) fn unescape(text: &str, quotes: bool) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => unescaped.push('\\'),
+            'n' => unescaped.push('\n'),
+            '"' if quotes => unescaped.push('"'),
+            _ => return None,
+        }
+    }
+
+    Some(unescaped)
+}
+
+// A minimal cursor over a single line of an exposition.
+pub(crate) struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(line: &'a str) -> Self {
+        Cursor { rest: line }
+    }
+
+    pub(crate) fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    // Skip spaces and tabs, returns true if anything was skipped.
+    pub(crate) fn skip_whitespace(&mut self) -> bool {
+        let trimmed = self.rest.trim_start_matches([' ', '\t']);
+        let skipped = trimmed.len() != self.rest.len();
+        self.rest = trimmed;
+        skipped
+    }
+
+    pub(crate) fn eat(&mut self, c: char) -> bool {
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Consume everything up to the next whitespace.
+    pub(crate) fn token(&mut self) -> &'a str {
+        let end = self.rest.find([' ', '\t']).unwrap_or(self.rest.len());
+        let (token, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        token
+    }
+
+    fn identifier(&mut self, colons: bool) -> Option<&'a str> {
+        let valid = |i: usize, c: char| {
+            c.is_ascii_alphabetic()
+                || c == '_'
+                || (colons && c == ':')
+                || (i > 0 && c.is_ascii_digit())
+        };
+        let end = self
+            .rest
+            .char_indices()
+            .find(|(i, c)| !valid(*i, *c))
+            .map(|(i, _)| i)
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return None;
+        }
+        let (identifier, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(identifier)
+    }
+
+    pub(crate) fn metric_name(&mut self) -> Option<&'a str> {
+        self.identifier(true)
+    }
+
+    pub(crate) fn label_name(&mut self) -> Option<&'a str> {
+        self.identifier(false)
+    }
+
+    // Consume a double-quoted, escaped string and return its unescaped content.
+    pub(crate) fn quoted(&mut self) -> Option<String> {
+        let content = self.rest.strip_prefix('"')?;
+        let mut escaped = false;
+        for (i, c) in content.char_indices() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => {
+                    self.rest = &content[i + 1..];
+                    return unescape(&content[..i], true);
+                }
+                _ => escaped = false,
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPOSITION: &str = r#"# HELP http_requests_total The total number of HTTP requests.
+# TYPE http_requests_total counter
+http_requests_total{method="post",code="200"} 1027 1395066363000
+http_requests_total{method="post",code="400"}    3 1395066363000
+
+# Escaping in label values:
+msdos_file_access_time_seconds{path="C:\\DIR\\FILE.TXT",error="Cannot find file:\n\"FILE.TXT\""} 1.458255915e9
+
+# Minimalistic line:
+metric_without_timestamp_and_labels 12.47
+
+# A weird metric from before the epoch:
+something_weird{problem="division by zero"} +Inf -3982045
+
+# A histogram, which has a pretty complex representation in the text format:
+# HELP http_request_duration_seconds A histogram of the request duration.
+# TYPE http_request_duration_seconds histogram
+http_request_duration_seconds_bucket{le="0.05"} 24054
+http_request_duration_seconds_bucket{le="0.1"} 33444
+http_request_duration_seconds_bucket{le="+Inf"} 144320
+http_request_duration_seconds_sum 53423
+http_request_duration_seconds_count 144320
+
+# Finally a summary, which has a complex representation, too:
+# HELP rpc_duration_seconds A summary of the RPC duration in seconds.
+# TYPE rpc_duration_seconds summary
+rpc_duration_seconds{quantile="0.01"} 3102
+rpc_duration_seconds{quantile="0.99"} 76656
+rpc_duration_seconds_sum 1.7560473e+07
+rpc_duration_seconds_count 2693
+"#;
+
+    #[test]
+    fn test_parse_text() {
+        let families = parse_text(EXPOSITION).unwrap();
+
+        let overview: Vec<(&str, MetricType, usize)> = families
+            .iter()
+            .map(|f| (f.name(), f.metric_type(), f.samples().len()))
+            .collect();
+
+        assert_eq!(
+            overview,
+            vec![
+                ("http_requests_total", MetricType::Counter, 2),
+                ("msdos_file_access_time_seconds", MetricType::Unknown, 1),
+                (
+                    "metric_without_timestamp_and_labels",
+                    MetricType::Unknown,
+                    1
+                ),
+                ("something_weird", MetricType::Unknown, 1),
+                ("http_request_duration_seconds", MetricType::Histogram, 5),
+                ("rpc_duration_seconds", MetricType::Summary, 4),
+            ]
+        );
+
+        assert_eq!(
+            families[0].help(),
+            Some("The total number of HTTP requests.")
+        );
+
+        let sample = &families[0].samples()[1];
+        assert_eq!(sample.labels().get("code").unwrap(), "400");
+        assert_eq!(sample.value(), 3.0);
+        assert_eq!(sample.timestamp(), Some(1395066363000));
+
+        let sample = &families[1].samples()[0];
+        assert_eq!(sample.labels().get("path").unwrap(), "C:\\DIR\\FILE.TXT");
+        assert_eq!(
+            sample.labels().get("error").unwrap(),
+            "Cannot find file:\n\"FILE.TXT\""
+        );
+
+        let sample = &families[3].samples()[0];
+        assert_eq!(sample.value(), f64::INFINITY);
+        assert_eq!(sample.timestamp(), Some(-3982045));
+
+        assert_eq!(
+            families[4].samples()[2].name(),
+            "http_request_duration_seconds_bucket"
+        );
+        assert_eq!(families[5].samples()[2].value(), 17560473.0);
+    }
+
+    #[test]
+    fn test_parse_text_for_error() {
+        let inputs = [
+            (
+                "# TYPE foo counter\n# TYPE foo gauge\n",
+                "the metrics exposition is invalid in line 2: second TYPE line for metric foo",
+            ),
+            (
+                "# TYPE foo enum\n",
+                "the metrics exposition is invalid in line 1: unknown metric type enum",
+            ),
+            (
+                "foo{bar=\"baz\" 1\n",
+                "the metrics exposition is invalid in line 1: expected ',' or '}' after a label",
+            ),
+            (
+                "foo 1\nfoo{bar=\"\\t\"} 1\n",
+                "the metrics exposition is invalid in line 2: invalid value of label bar",
+            ),
+            (
+                "foo one\n",
+                "the metrics exposition is invalid in line 1: invalid value one",
+            ),
+            (
+                "foo 1 2 3\n",
+                "the metrics exposition is invalid in line 1: unexpected trailing content 3",
+            ),
+        ];
+
+        for (input, message) in inputs {
+            assert_eq!(parse_text(input).unwrap_err().to_string(), message);
+        }
+    }
+
+    #[test]
+    fn test_encode_text_roundtrip() {
+        let families = parse_text(EXPOSITION).unwrap();
+
+        let encoded = encode_text(&families);
+
+        assert!(encoded.contains(
+            "msdos_file_access_time_seconds{error=\"Cannot find file:\\n\\\"FILE.TXT\\\"\",path=\"C:\\\\DIR\\\\FILE.TXT\"} 1458255915\n"
+        ));
+        assert!(encoded.contains("# TYPE something_weird untyped\nsomething_weird{problem=\"division by zero\"} +Inf -3982045\n"));
+
+        assert_eq!(parse_text(&encoded).unwrap(), families);
+    }
+
+    const OPENMETRICS: &str = r#"# TYPE acme_http_router_request_seconds summary
+# UNIT acme_http_router_request_seconds seconds
+# HELP acme_http_router_request_seconds Latency though all of ACME's HTTP request router.
+acme_http_router_request_seconds_sum{path="/api/v1",method="GET"} 9036.32
+acme_http_router_request_seconds_count{path="/api/v1",method="GET"} 807283.0
+acme_http_router_request_seconds_created{path="/api/v1",method="GET"} 1605281325.0
+# TYPE foo counter
+foo_total 17.0 1520879607.789 # {trace_id="KOO5S4vxi0o"} 0.67
+foo_created 1520872607.123
+# TYPE bar gaugehistogram
+bar_bucket{le="0.01"} 20.0
+bar_bucket{le="+Inf"} 17.0 # {} 9.8 1520879607.789
+bar_gcount 42.0
+bar_gsum 3.0
+# TYPE build info
+build_info{version="1.2.3"} 1
+# TYPE feature stateset
+feature{feature="a"} 1
+feature{feature="b"} 0
+# EOF
+"#;
+
+    #[test]
+    fn test_parse_openmetrics() {
+        let families = parse_openmetrics(OPENMETRICS).unwrap();
+
+        let overview: Vec<(&str, MetricType, usize)> = families
+            .iter()
+            .map(|f| (f.name(), f.metric_type(), f.samples().len()))
+            .collect();
+
+        assert_eq!(
+            overview,
+            vec![
+                ("acme_http_router_request_seconds", MetricType::Summary, 3),
+                ("foo", MetricType::Counter, 2),
+                ("bar", MetricType::GaugeHistogram, 4),
+                ("build", MetricType::Info, 1),
+                ("feature", MetricType::Stateset, 2),
+            ]
+        );
+
+        assert_eq!(families[0].unit(), Some("seconds"));
+        assert_eq!(
+            families[0].help(),
+            Some("Latency though all of ACME's HTTP request router.")
+        );
+
+        let sample = &families[1].samples()[0];
+        assert_eq!(sample.timestamp(), Some(1520879607789));
+
+        let exemplar = sample.exemplar().unwrap();
+        assert_eq!(exemplar.labels().get("trace_id").unwrap(), "KOO5S4vxi0o");
+        assert_eq!(exemplar.value(), 0.67);
+        assert_eq!(exemplar.timestamp(), None);
+
+        assert_eq!(families[1].samples()[1].name(), "foo_created");
+
+        let exemplar = families[2].samples()[1].exemplar().unwrap();
+        assert!(exemplar.labels().is_empty());
+        assert_eq!(exemplar.timestamp(), Some(1520879607789));
+    }
+
+    #[test]
+    fn test_parse_openmetrics_for_error() {
+        let inputs = [
+            (
+                "# TYPE foo counter\nfoo_total 1\n",
+                "the metrics exposition is invalid in line 2: missing # EOF",
+            ),
+            (
+                "# EOF\nfoo 1\n",
+                "the metrics exposition is invalid in line 2: unexpected content after # EOF",
+            ),
+            (
+                "# TYPE foo_seconds gauge\n# UNIT foo_seconds bytes\n# EOF\n",
+                "the metrics exposition is invalid in line 2: unit bytes is not a suffix of metric foo_seconds",
+            ),
+            (
+                "# TYPE foo untyped\n# EOF\n",
+                "the metrics exposition is invalid in line 1: unknown metric type untyped",
+            ),
+            (
+                "# a comment\n# EOF\n",
+                "the metrics exposition is invalid in line 1: invalid comment",
+            ),
+        ];
+
+        for (input, message) in inputs {
+            assert_eq!(parse_openmetrics(input).unwrap_err().to_string(), message);
+        }
+
+        assert!(parse_text("foo 1 # {trace_id=\"a\"} 1\n").is_err());
+    }
+
+    #[test]
+    fn test_encode_openmetrics_roundtrip() {
+        let families = parse_openmetrics(OPENMETRICS).unwrap();
+
+        let encoded = encode_openmetrics(&families);
+
+        assert!(encoded.starts_with("# HELP acme_http_router_request_seconds Latency though all of ACME's HTTP request router.\n# TYPE acme_http_router_request_seconds summary\n# UNIT acme_http_router_request_seconds seconds\n"));
+        assert!(encoded.contains("foo_total 17 1520879607.789 # {trace_id=\"KOO5S4vxi0o\"} 0.67\n"));
+        assert!(encoded.contains("bar_bucket{le=\"+Inf\"} 17 # {} 9.8 1520879607.789\n"));
+        assert!(encoded.ends_with("feature{feature=\"b\"} 0\n# EOF\n"));
+
+        assert_eq!(parse_openmetrics(&encoded).unwrap(), families);
+    }
+}
@@ -0,0 +1,42 @@
+// Query results shared by the tests of several modules.
+use crate::response::{RangeVector, Sample};
+use std::collections::HashMap;
+
+// The labels of an `up` time series.
+pub(crate) fn metric(job: &str, instance: Option<&str>) -> HashMap<String, String> {
+    let mut metric = HashMap::from([
+        ("__name__".to_string(), "up".to_string()),
+        ("job".to_string(), job.to_string()),
+    ]);
+    if let Some(instance) = instance {
+        metric.insert("instance".to_string(), instance.to_string());
+    }
+    metric
+}
+
+// An `up` time series with the given timestamps (in seconds) and values.
+pub(crate) fn range_vector(
+    job: &str,
+    instance: Option<&str>,
+    samples: &[(f64, f64)],
+) -> RangeVector {
+    let samples = samples.iter().map(|(t, v)| Sample::new(*t, *v)).collect();
+    RangeVector::new(metric(job, instance), samples)
+}
+
+// A range query result with a step of 15s. The first series misses a sample, the second
+// one has no instance label and only a single sample.
+pub(crate) fn matrix() -> Vec<RangeVector> {
+    vec![
+        range_vector(
+            "node",
+            Some("a:9100"),
+            &[
+                (1435781430.0, 1.0),
+                (1435781445.0, 0.0),
+                (1435781475.0, 1.0),
+            ],
+        ),
+        range_vector("prometheus", None, &[(1435781445.0, 1.0)]),
+    ]
+}
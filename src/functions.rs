@@ -37,6 +37,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -70,6 +71,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -104,6 +106,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -137,6 +140,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -171,6 +175,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -203,6 +208,7 @@ create_function! {
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert_eq!(value, 3.0);
@@ -238,6 +244,7 @@ pub fn clamp(vector: InstantVector, min: f64, max: f64) -> InstantVector {
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert_eq!(value, 3.0);
@@ -273,6 +280,7 @@ pub fn clamp_max(vector: InstantVector, max: f64) -> InstantVector {
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert_eq!(value, 5.0);
@@ -309,6 +317,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!((1.0..=31.0).contains(&value));
@@ -342,6 +351,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!((0.0..=6.0).contains(&value));
@@ -375,6 +385,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!((28.0..=31.0).contains(&value));
@@ -468,6 +479,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -501,6 +513,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -535,6 +548,7 @@ create_function! {
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert!(value.is_normal());
@@ -572,6 +586,7 @@ pub fn histogram_quantile(quantile: f64, vector: InstantVector) -> InstantVector
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert!(value.is_normal());
@@ -616,6 +631,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!((0.0..=23.0).contains(&value));
@@ -651,6 +667,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -686,6 +703,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -721,6 +739,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -867,6 +886,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -900,6 +920,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -933,6 +954,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -966,6 +988,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!((0.0..=59.0).contains(&value));
@@ -999,6 +1022,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!((1.0..=12.0).contains(&value));
@@ -1031,6 +1055,7 @@ create_function! {
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert!(value.is_normal());
@@ -1126,6 +1151,7 @@ create_function! {
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert_eq!(value, 1.0);
@@ -1195,6 +1221,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -1227,6 +1254,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1259,6 +1287,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1291,6 +1320,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value > 1644417828.0);
@@ -1323,6 +1353,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value >= 2022.0);
@@ -1356,6 +1387,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1389,6 +1421,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1422,6 +1455,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1455,6 +1489,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1488,6 +1523,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1520,6 +1556,7 @@ create_function! {
 ///         .get(0)
 ///         .unwrap()
 ///         .sample()
+///         .unwrap()
 ///         .value();
 ///
 ///     assert!(value.is_normal());
@@ -1616,6 +1653,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1649,6 +1687,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_normal());
@@ -1682,6 +1721,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -1715,6 +1755,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -1748,6 +1789,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -1781,6 +1823,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -1814,6 +1857,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -1847,6 +1891,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_infinite());
@@ -1880,6 +1925,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -1913,6 +1959,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 1.0);
@@ -1946,6 +1993,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -1979,6 +2027,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -2012,6 +2061,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -2045,6 +2095,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 0.0);
@@ -2078,6 +2129,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert_eq!(value, 45.0);
@@ -2111,6 +2163,7 @@ create_function! {
     ///         .get(0)
     ///         .unwrap()
     ///         .sample()
+    ///         .unwrap()
     ///         .value();
     ///
     ///     assert!(value.is_infinite());
@@ -67,7 +67,9 @@
 //!
 //!     if let Some(result) = response.as_instant() {
 //!         let first = result.get(0).unwrap();
-//!         println!("Received a total of {} HTTP requests", first.sample().value());
+//!         if let Some(sample) = first.sample() {
+//!             println!("Received a total of {} HTTP requests", sample.value());
+//!         }
 //!     }
 //!     Ok(())
 //! }
@@ -117,7 +119,7 @@
 //!
 //!     let set = vec![s1, s2];
 //!
-//!     let response = client.series(&set, None, None).await;
+//!     let response = client.series(&set, None, None, None).await;
 //!
 //!     assert!(response.is_ok());
 //!
@@ -158,10 +160,13 @@
 //!
 //! # Compatibility
 //!
-//! This version of the crate is compatible with Prometheus server >= v2.31.
-//! Versions as "old" as v2.26 are compatible as well when some features are not used. For example trigonometric functions
-//! were only introduced with v2.31. [Client::targets] will fail as well because the result as returned by the API
-//! changed between v2.26 and v2.31.
+//! This version of the crate is compatible with Prometheus server >= v2.26, including v3.x.
+//! Some features require more recent versions, e.g. trigonometric functions were only introduced with v2.31.
+//! Response fields that are not returned by all of these versions are modeled as [Option]s, i.e. responses are
+//! parsed the same way regardless of the server version.
+//! Use [Client::detect_capabilities] to have the client reject expressions with functions the server does not
+//! support (see [Capabilities]) before they are sent. Servers that implement the Prometheus API with their own
+//! versioning, e.g. Thanos, require [Client::with_capabilities] instead.
 //!
 //! # Supported operations
 //!
@@ -177,8 +182,26 @@
 //! - [x] Target metadata
 //! - [x] Metric metadata
 //! - [x] Alertmanager service discovery status
-//! - [ ] Prometheus config
-//! - [ ] Prometheus runtime & build information
+//! - [x] Exemplars
+//! - [x] Prometheus config
+//! - [x] Prometheus runtime & build information
+//! - [x] TSDB statistics
+//! - [x] WAL replay statistics
+//! - [x] TSDB admin API (snapshots, deleting series, cleaning tombstones)
+//! - [x] Management API (health, readiness, reload, shutdown)
+//! - [x] Remote write (see [remote_write])
+//! - [x] Remote read (see [remote_read])
+//! - [x] Federation (see [exposition] for the text exposition and OpenMetrics formats)
+//! - [x] OpenMetrics backfill files from range query results (see [backfill])
+//! - [x] Pushgateway (see [pushgateway])
+//! - [x] Alertmanager API v2 (see [alertmanager])
+//! - [x] Typed Prometheus configuration (see [config])
+//! - [x] Relabeling simulation, e.g. to explain dropped targets (see [relabel])
+//! - [x] Streaming of large query results one series at a time (see [stream])
+//! - [x] Columnar representation of range query results (see [columnar])
+//! - [x] Apache Arrow and Parquet export of query results (see `arrow`, requires the `arrow` feature)
+//! - [x] CSV and JSON Lines export of query results (see [export])
+//! - [x] Alignment of range query results onto a time-indexed table (see [table])
 //!
 //! # Notes
 //!
@@ -198,22 +221,48 @@
 //!
 //! * Subqueries are not supported (only as custom query)
 //! * PromQL functions that do not take a range / instant vector as an argument are not supported (only as custom query), e.g. pi()
-//! * The [String](https://prometheus.io/docs/prometheus/latest/querying/api/#strings) result type is not supported
 pub mod aggregations;
+pub mod alertmanager;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod backfill;
 mod client;
+pub mod columnar;
+pub mod config;
 mod error;
+pub mod export;
+pub mod exposition;
+#[cfg(test)]
+mod fixtures;
 pub mod functions;
+mod proto;
+pub mod pushgateway;
+pub mod relabel;
+pub mod remote_read;
+pub mod remote_write;
 pub mod response;
 mod selector;
+pub mod stream;
+pub mod table;
+#[cfg(test)]
+mod test_server;
 mod util;
 mod vector;
+mod version;
 pub use self::client::Client;
 pub use self::error::Error;
 pub use self::selector::Selector;
 pub use self::util::Aggregate;
+pub use self::util::AlertState;
 pub use self::util::Group;
 pub use self::util::Match;
+pub use self::util::RuleFilter;
+pub use self::util::RuleHealth;
 pub use self::util::RuleType;
+pub use self::util::TargetHealth;
 pub use self::util::TargetState;
 pub use self::vector::InstantVector;
 pub use self::vector::RangeVector;
+pub use self::vector::StringLiteral;
+pub use self::version::Capabilities;
+pub use self::version::Version;
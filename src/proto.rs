@@ -0,0 +1,146 @@
+//! Protobuf messages of the Prometheus remote storage protocols, mirroring
+//! `prompb/types.proto`, `prompb/remote.proto` and `prompb/io/prometheus/write/v2/types.proto`
+//! of the Prometheus repository. Only the fields this crate makes use of are declared.
+
+/// Messages of the remote write 1.0 and remote read protocols (package `prometheus`).
+pub(crate) mod v1 {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct WriteRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub(crate) timeseries: Vec<TimeSeries>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct TimeSeries {
+        #[prost(message, repeated, tag = "1")]
+        pub(crate) labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        pub(crate) samples: Vec<Sample>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Label {
+        #[prost(string, tag = "1")]
+        pub(crate) name: String,
+        #[prost(string, tag = "2")]
+        pub(crate) value: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Sample {
+        #[prost(double, tag = "1")]
+        pub(crate) value: f64,
+        #[prost(int64, tag = "2")]
+        pub(crate) timestamp: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct ReadRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub(crate) queries: Vec<Query>,
+        // Values of `ReadRequest.ResponseType`: SAMPLES = 0, STREAMED_XOR_CHUNKS = 1.
+        #[prost(int32, repeated, tag = "2")]
+        pub(crate) accepted_response_types: Vec<i32>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Query {
+        #[prost(int64, tag = "1")]
+        pub(crate) start_timestamp_ms: i64,
+        #[prost(int64, tag = "2")]
+        pub(crate) end_timestamp_ms: i64,
+        #[prost(message, repeated, tag = "3")]
+        pub(crate) matchers: Vec<LabelMatcher>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct LabelMatcher {
+        // Values of `LabelMatcher.Type`: EQ = 0, NEQ = 1, RE = 2, NRE = 3.
+        #[prost(int32, tag = "1")]
+        pub(crate) r#type: i32,
+        #[prost(string, tag = "2")]
+        pub(crate) name: String,
+        #[prost(string, tag = "3")]
+        pub(crate) value: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct ReadResponse {
+        #[prost(message, repeated, tag = "1")]
+        pub(crate) results: Vec<QueryResult>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct QueryResult {
+        #[prost(message, repeated, tag = "1")]
+        pub(crate) timeseries: Vec<TimeSeries>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct ChunkedReadResponse {
+        #[prost(message, repeated, tag = "1")]
+        pub(crate) chunked_series: Vec<ChunkedSeries>,
+        #[prost(int64, tag = "2")]
+        pub(crate) query_index: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct ChunkedSeries {
+        #[prost(message, repeated, tag = "1")]
+        pub(crate) labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        pub(crate) chunks: Vec<Chunk>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Chunk {
+        #[prost(int64, tag = "1")]
+        pub(crate) min_time_ms: i64,
+        #[prost(int64, tag = "2")]
+        pub(crate) max_time_ms: i64,
+        // Values of `Chunk.Encoding`: UNKNOWN = 0, XOR = 1, HISTOGRAM = 2, FLOAT_HISTOGRAM = 3.
+        #[prost(int32, tag = "3")]
+        pub(crate) r#type: i32,
+        #[prost(bytes = "vec", tag = "4")]
+        pub(crate) data: Vec<u8>,
+    }
+}
+
+/// Messages of the remote write 2.0 protocol (package `io.prometheus.write.v2`).
+pub(crate) mod v2 {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Request {
+        #[prost(string, repeated, tag = "4")]
+        pub(crate) symbols: Vec<String>,
+        #[prost(message, repeated, tag = "5")]
+        pub(crate) timeseries: Vec<TimeSeries>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct TimeSeries {
+        #[prost(uint32, repeated, tag = "1")]
+        pub(crate) labels_refs: Vec<u32>,
+        #[prost(message, repeated, tag = "2")]
+        pub(crate) samples: Vec<Sample>,
+        #[prost(message, optional, tag = "5")]
+        pub(crate) metadata: Option<Metadata>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Sample {
+        #[prost(double, tag = "1")]
+        pub(crate) value: f64,
+        #[prost(int64, tag = "2")]
+        pub(crate) timestamp: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Metadata {
+        #[prost(int32, tag = "1")]
+        pub(crate) r#type: i32,
+        #[prost(uint32, tag = "3")]
+        pub(crate) help_ref: u32,
+        #[prost(uint32, tag = "4")]
+        pub(crate) unit_ref: u32,
+    }
+}
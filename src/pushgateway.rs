@@ -0,0 +1,493 @@
+//! A client for the [Pushgateway](https://github.com/prometheus/pushgateway) that pushes metric
+//! families (see [crate::exposition]) on behalf of batch jobs.
+//!
+//! ```rust
+//! use prometheus_http_query::exposition::{MetricFamily, MetricSample};
+//! use prometheus_http_query::pushgateway::Pushgateway;
+//! use prometheus_http_query::response::MetricType;
+//! use prometheus_http_query::{Client, Error};
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     // Reuse the HTTP configuration (e.g. TLS settings) of a Prometheus client.
+//!     let client = Client::default();
+//!     let pushgateway = Pushgateway::from(client.inner().clone(), "http://127.0.0.1:9091")?;
+//!
+//!     let family = MetricFamily::new("backup_last_success_timestamp_seconds", MetricType::Gauge)
+//!         .with_sample(MetricSample::new("backup_last_success_timestamp_seconds", 1623345960.0));
+//!
+//!     pushgateway.put("backup", &[("instance", "db-1")], &[family]).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::client::check_response;
+use crate::error::{Error, InvalidFunctionArgument, PushRejected};
+use crate::exposition::{self, MetricFamily, MetricSample};
+use crate::response::MetricType;
+use base64::Engine;
+use serde::Deserialize;
+use std::collections::HashMap;
+use url::Url;
+
+mod de {
+    use serde::Deserialize;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Deserialize)]
+    pub(crate) struct MetricGroup {
+        pub(crate) labels: HashMap<String, String>,
+        #[serde(default)]
+        pub(crate) last_push_successful: bool,
+        #[serde(flatten)]
+        pub(crate) families: BTreeMap<String, Family>,
+    }
+
+    #[derive(Deserialize)]
+    pub(crate) struct Family {
+        #[serde(rename = "type")]
+        pub(crate) metric_type: String,
+        #[serde(default)]
+        pub(crate) help: String,
+        pub(crate) metrics: Vec<Metric>,
+    }
+
+    #[derive(Deserialize)]
+    pub(crate) struct Metric {
+        #[serde(default)]
+        pub(crate) labels: BTreeMap<String, String>,
+        pub(crate) value: Option<String>,
+        pub(crate) buckets: Option<HashMap<String, String>>,
+        pub(crate) quantiles: Option<HashMap<String, String>>,
+        pub(crate) count: Option<String>,
+        pub(crate) sum: Option<String>,
+    }
+}
+
+/// A group of metrics as stored by the Pushgateway, identified by its grouping key.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "de::MetricGroup")]
+pub struct MetricGroup {
+    pub(crate) labels: HashMap<String, String>,
+    pub(crate) last_push_successful: bool,
+    pub(crate) families: Vec<MetricFamily>,
+}
+
+impl MetricGroup {
+    /// Get the grouping key of this group, including the `job` label.
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// Check whether the last push to this group was successful.
+    pub fn last_push_successful(&self) -> bool {
+        self.last_push_successful
+    }
+
+    /// Get the metric families of this group, including the `push_time_seconds` and
+    /// `push_failure_time_seconds` families maintained by the Pushgateway.
+    pub fn families(&self) -> &[MetricFamily] {
+        &self.families
+    }
+
+    /// Get the time of the last successful push to this group as Unix timestamp in seconds.
+    pub fn push_time(&self) -> Option<f64> {
+        self.families
+            .iter()
+            .find(|f| f.name() == "push_time_seconds")
+            .and_then(|f| f.samples().first())
+            .map(|s| s.value())
+    }
+}
+
+impl std::convert::TryFrom<de::MetricGroup> for MetricGroup {
+    type Error = String;
+
+    fn try_from(group: de::MetricGroup) -> Result<Self, Self::Error> {
+        let mut families = vec![];
+
+        for (name, family) in group.families {
+            let metric_type = match family.metric_type.as_str() {
+                "COUNTER" => MetricType::Counter,
+                "GAUGE" => MetricType::Gauge,
+                "HISTOGRAM" => MetricType::Histogram,
+                "GAUGE_HISTOGRAM" => MetricType::GaugeHistogram,
+                "SUMMARY" => MetricType::Summary,
+                _ => MetricType::Unknown,
+            };
+
+            let mut result = MetricFamily::new(&name, metric_type);
+
+            if !family.help.is_empty() {
+                result.help = Some(family.help);
+            }
+
+            for metric in family.metrics {
+                let mut push = |suffix: &str, extra: Option<(&str, &str)>, value: &str| {
+                    let value = exposition::parse_value(value)
+                        .ok_or_else(|| format!("invalid value {} of metric {}", value, name))?;
+                    let mut sample = MetricSample::new(&format!("{}{}", name, suffix), value);
+                    sample.labels = metric.labels.clone();
+                    if let Some((label, label_value)) = extra {
+                        sample
+                            .labels
+                            .insert(label.to_string(), label_value.to_string());
+                    }
+                    result.samples.push(sample);
+                    Ok::<(), String>(())
+                };
+
+                if let Some(value) = &metric.value {
+                    push("", None, value)?;
+                }
+
+                let mut buckets: Vec<(&String, &String)> =
+                    metric.buckets.iter().flatten().collect();
+                buckets.sort_by(|a, b| sort_key(a.0).total_cmp(&sort_key(b.0)));
+
+                for (le, value) in buckets {
+                    push("_bucket", Some(("le", le)), value)?;
+                }
+
+                let mut quantiles: Vec<(&String, &String)> =
+                    metric.quantiles.iter().flatten().collect();
+                quantiles.sort_by(|a, b| sort_key(a.0).total_cmp(&sort_key(b.0)));
+
+                for (quantile, value) in quantiles {
+                    push("", Some(("quantile", quantile)), value)?;
+                }
+
+                if let Some(sum) = &metric.sum {
+                    push("_sum", None, sum)?;
+                }
+
+                if let Some(count) = &metric.count {
+                    push("_count", None, count)?;
+                }
+            }
+
+            families.push(result);
+        }
+
+        Ok(MetricGroup {
+            labels: group.labels,
+            last_push_successful: group.last_push_successful,
+            families,
+        })
+    }
+}
+
+fn sort_key(boundary: &str) -> f64 {
+    exposition::parse_value(boundary).unwrap_or(f64::NAN)
+}
+
+/// A client for the Pushgateway.
+#[derive(Debug, Clone)]
+pub struct Pushgateway {
+    pub(crate) client: reqwest::Client,
+    pub(crate) url: Url,
+}
+
+impl std::convert::TryFrom<&str> for Pushgateway {
+    type Error = crate::error::Error;
+
+    /// Create a Pushgateway client from the URL of a Pushgateway, e.g. `http://127.0.0.1:9091`.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::pushgateway::Pushgateway;
+    /// use std::convert::TryFrom;
+    ///
+    /// let pushgateway = Pushgateway::try_from("http://127.0.0.1:9091");
+    /// assert!(pushgateway.is_ok());
+    /// ```
+    fn try_from(url: &str) -> Result<Self, Self::Error> {
+        Pushgateway::from(reqwest::Client::new(), url)
+    }
+}
+
+impl Pushgateway {
+    /// Create a Pushgateway client from a custom [reqwest::Client] and the URL of a Pushgateway.
+    /// Use [crate::Client::inner] to share the configuration of a Prometheus client.
+    pub fn from(client: reqwest::Client, url: &str) -> Result<Self, Error> {
+        let url = Url::parse(url).map_err(Error::UrlParse)?;
+        Ok(Pushgateway { client, url })
+    }
+
+    /// Push metric families to the group identified by the job name and grouping key,
+    /// replacing only the metrics with the same names as the pushed ones (HTTP `POST`).
+    ///
+    /// Note that the Pushgateway rejects samples with timestamps.
+    pub async fn push(
+        &self,
+        job: &str,
+        grouping_key: &[(&str, &str)],
+        families: &[MetricFamily],
+    ) -> Result<(), Error> {
+        let url = self.group_url(job, grouping_key)?;
+        let request = self.client.post(url);
+        self.send_metrics(request, families).await
+    }
+
+    /// Push metric families to the group identified by the job name and grouping key,
+    /// replacing all metrics of this group (HTTP `PUT`).
+    ///
+    /// Note that the Pushgateway rejects samples with timestamps.
+    pub async fn put(
+        &self,
+        job: &str,
+        grouping_key: &[(&str, &str)],
+        families: &[MetricFamily],
+    ) -> Result<(), Error> {
+        let url = self.group_url(job, grouping_key)?;
+        let request = self.client.put(url);
+        self.send_metrics(request, families).await
+    }
+
+    /// Delete all metrics of the group identified by the job name and grouping key.
+    pub async fn delete(&self, job: &str, grouping_key: &[(&str, &str)]) -> Result<(), Error> {
+        let url = self.group_url(job, grouping_key)?;
+        let response = self
+            .client
+            .delete(url)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        check_push_response(response).await
+    }
+
+    /// List all metric groups that are currently stored by the Pushgateway.
+    pub async fn metric_groups(&self) -> Result<Vec<MetricGroup>, Error> {
+        let mut url = self.url.clone();
+
+        url.path_segments_mut()
+            .map_err(|_| Error::UrlParse(url::ParseError::RelativeUrlWithCannotBeABaseBase))?
+            .pop_if_empty()
+            .extend(&["api", "v1", "metrics"]);
+
+        let response = self.client.get(url).send().await.map_err(Error::Reqwest)?;
+
+        let mut map = check_response(response).await?;
+
+        let data = map.remove("data").ok_or(Error::MissingField)?;
+
+        serde_json::from_value(data).map_err(Error::ResponseParse)
+    }
+
+    async fn send_metrics(
+        &self,
+        request: reqwest::RequestBuilder,
+        families: &[MetricFamily],
+    ) -> Result<(), Error> {
+        let response = request
+            .header(reqwest::header::CONTENT_TYPE, "text/plain; version=0.0.4")
+            .body(exposition::encode_text(families))
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        check_push_response(response).await
+    }
+
+    // Build the URL of a metric group, e.g. ".../metrics/job/backup/instance/db-1".
+    // Values that contain a slash or are empty are base64-encoded (URL-safe alphabet),
+    // which is indicated by appending "@base64" to the label name.
+    fn group_url(&self, job: &str, grouping_key: &[(&str, &str)]) -> Result<Url, Error> {
+        if job.is_empty() {
+            return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                message: String::from("the job name of a metric group must not be empty"),
+            }));
+        }
+
+        if grouping_key.iter().any(|(name, _)| *name == "job") {
+            return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                message: String::from("the grouping key must not contain the job label"),
+            }));
+        }
+
+        let mut url = self.url.clone();
+
+        {
+            let mut segments = url
+                .path_segments_mut()
+                .map_err(|_| Error::UrlParse(url::ParseError::RelativeUrlWithCannotBeABaseBase))?;
+
+            segments.pop_if_empty().push("metrics");
+
+            for (name, value) in std::iter::once(&("job", job)).chain(grouping_key) {
+                if value.is_empty() || value.contains('/') {
+                    let encoded = base64::engine::general_purpose::URL_SAFE.encode(value);
+                    segments
+                        .push(&format!("{}@base64", name))
+                        .push(if encoded.is_empty() { "=" } else { &encoded });
+                } else {
+                    segments.push(name).push(value);
+                }
+            }
+        }
+
+        Ok(url)
+    }
+}
+
+// The Pushgateway responds with a plain text error message if a push is rejected.
+async fn check_push_response(response: reqwest::Response) -> Result<(), Error> {
+    let status = response.status();
+
+    if status.is_success() {
+        return Ok(());
+    }
+
+    let message = response.text().await.map_err(Error::Reqwest)?;
+
+    Err(Error::PushRejected(PushRejected {
+        status: status.as_u16(),
+        message: message.trim().to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_group_url() {
+        let pushgateway = Pushgateway::try_from("http://127.0.0.1:9091").unwrap();
+
+        let url = pushgateway
+            .group_url(
+                "backup",
+                &[("instance", "db-1"), ("path", "/var/tmp"), ("env", "")],
+            )
+            .unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "http://127.0.0.1:9091/metrics/job/backup/instance/db-1/path@base64/L3Zhci90bXA=/env@base64/="
+        );
+
+        let pushgateway = Pushgateway::try_from("https://example.com/pushgateway/").unwrap();
+
+        let url = pushgateway.group_url("a/b c", &[]).unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://example.com/pushgateway/metrics/job@base64/YS9iIGM="
+        );
+
+        assert!(pushgateway.group_url("", &[]).is_err());
+        assert!(pushgateway
+            .group_url("backup", &[("job", "other")])
+            .is_err());
+    }
+
+    #[test]
+    fn test_metric_group_deserialization() {
+        let data = r#"
+{
+  "labels": { "job": "backup", "instance": "db-1" },
+  "last_push_successful": true,
+  "backup_duration_seconds": {
+    "time_stamp": "2021-06-10T17:26:00.000Z",
+    "type": "HISTOGRAM",
+    "help": "Duration of the backup.",
+    "metrics": [
+      {
+        "labels": { "instance": "db-1", "job": "backup" },
+        "buckets": { "+Inf": "3", "1": "1", "10": "2" },
+        "count": "3",
+        "sum": "42.5"
+      }
+    ]
+  },
+  "push_time_seconds": {
+    "time_stamp": "2021-06-10T17:26:00.000Z",
+    "type": "GAUGE",
+    "help": "Last Unix time when changing this group in the Pushgateway succeeded.",
+    "metrics": [
+      { "labels": { "instance": "db-1", "job": "backup" }, "value": "1.623345960e+09" }
+    ]
+  }
+}
+"#;
+        let group: MetricGroup = serde_json::from_str(data).unwrap();
+
+        assert_eq!(group.labels().get("instance").unwrap(), "db-1");
+        assert!(group.last_push_successful());
+        assert_eq!(group.push_time(), Some(1623345960.0));
+
+        let histogram = &group.families()[0];
+        assert_eq!(histogram.metric_type(), MetricType::Histogram);
+
+        let samples: Vec<(&str, Option<&str>, f64)> = histogram
+            .samples()
+            .iter()
+            .map(|s| {
+                (
+                    s.name(),
+                    s.labels().get("le").map(|l| l.as_str()),
+                    s.value(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            samples,
+            vec![
+                ("backup_duration_seconds_bucket", Some("1"), 1.0),
+                ("backup_duration_seconds_bucket", Some("10"), 2.0),
+                ("backup_duration_seconds_bucket", Some("+Inf"), 3.0),
+                ("backup_duration_seconds_sum", None, 42.5),
+                ("backup_duration_seconds_count", None, 3.0),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push() {
+        let (url, received) = test_server::start(200, "").await;
+
+        let pushgateway = Pushgateway::try_from(url.as_str()).unwrap();
+
+        let family = MetricFamily::new("backup_size_bytes", MetricType::Gauge)
+            .with_sample(MetricSample::new("backup_size_bytes", 1024.0));
+
+        pushgateway
+            .push("backup", &[], std::slice::from_ref(&family))
+            .await
+            .unwrap();
+        pushgateway
+            .put("backup", &[("instance", "db-1")], &[family])
+            .await
+            .unwrap();
+        pushgateway.delete("backup", &[]).await.unwrap();
+
+        let received: Vec<String> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+
+        assert_eq!(
+            received[0],
+            "POST /metrics/job/backup HTTP/1.1\n# TYPE backup_size_bytes gauge\nbackup_size_bytes 1024\n"
+        );
+        assert!(received[1].starts_with("PUT /metrics/job/backup/instance/db-1 HTTP/1.1\n"));
+        assert_eq!(received[2], "DELETE /metrics/job/backup HTTP/1.1\n");
+    }
+
+    #[tokio::test]
+    async fn test_push_rejected() {
+        let (url, _) = test_server::start(400, "pushed metrics are invalid or inconsistent").await;
+
+        let pushgateway = Pushgateway::try_from(url.as_str()).unwrap();
+
+        let result = pushgateway.delete("backup", &[]).await;
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "the push was rejected with status 400: pushed metrics are invalid or inconsistent"
+        );
+    }
+}
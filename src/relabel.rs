@@ -0,0 +1,452 @@
+//! A local implementation of Prometheus [relabeling](https://prometheus.io/docs/prometheus/latest/configuration/configuration/#relabel_config)
+//! that records every step, e.g. to find out which rule dropped a target.
+//!
+//! ```rust
+//! use prometheus_http_query::config::PrometheusConfig;
+//! use prometheus_http_query::relabel;
+//! use prometheus_http_query::{Client, Error};
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let client = Client::default();
+//!
+//!     let config = client.config().await?.parse()?;
+//!
+//!     for target in client.targets(None, None).await?.dropped() {
+//!         let relabeling = relabel::explain_dropped(&config, target)?;
+//!
+//!         if let Some(step) = relabeling.dropped_by() {
+//!             println!("dropped by rule #{}: {:?}", step.index(), step.config());
+//!         }
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::config::{PrometheusConfig, RelabelAction, RelabelConfig};
+use crate::error::{Error, InvalidFunctionArgument};
+use crate::exposition::Cursor;
+use crate::response::DroppedTarget;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+
+/// The outcome of applying a list of relabeling rules to a label set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relabeling {
+    pub(crate) input: BTreeMap<String, String>,
+    pub(crate) steps: Vec<RelabelStep>,
+}
+
+impl Relabeling {
+    /// Get the labels before relabeling.
+    pub fn input(&self) -> &BTreeMap<String, String> {
+        &self.input
+    }
+
+    /// Get all steps that were applied, one per rule. No further rules are applied once
+    /// a rule drops the label set.
+    pub fn steps(&self) -> &[RelabelStep] {
+        &self.steps
+    }
+
+    /// Get the resulting labels, or `None` if the label set was dropped.
+    pub fn labels(&self) -> Option<&BTreeMap<String, String>> {
+        match self.steps.last() {
+            Some(step) if step.dropped => None,
+            Some(step) => Some(&step.labels),
+            None => Some(&self.input),
+        }
+    }
+
+    /// Check whether the label set was dropped.
+    pub fn is_dropped(&self) -> bool {
+        self.dropped_by().is_some()
+    }
+
+    /// Get the step that dropped the label set, if any.
+    pub fn dropped_by(&self) -> Option<&RelabelStep> {
+        self.steps.last().filter(|step| step.dropped)
+    }
+}
+
+/// A single relabeling rule that was applied to a label set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelabelStep {
+    pub(crate) index: usize,
+    pub(crate) config: RelabelConfig,
+    pub(crate) labels: BTreeMap<String, String>,
+    pub(crate) dropped: bool,
+}
+
+impl RelabelStep {
+    /// Get the position of the rule in the list of rules, starting at 0.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the rule.
+    pub fn config(&self) -> &RelabelConfig {
+        &self.config
+    }
+
+    /// Get the labels after this rule was applied. If the rule dropped the label set, these
+    /// are the labels the rule was applied to.
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    /// Check whether this rule dropped the label set.
+    pub fn is_dropped(&self) -> bool {
+        self.dropped
+    }
+}
+
+/// Apply a list of relabeling rules to a label set the same way Prometheus does, recording
+/// the labels after each rule.
+///
+/// ```rust
+/// use prometheus_http_query::config::{RelabelAction, RelabelConfig};
+/// use prometheus_http_query::relabel;
+/// use std::collections::HashMap;
+///
+/// let labels = HashMap::from([
+///     ("__address__".to_string(), "10.0.0.1:8080".to_string()),
+///     ("__meta_kubernetes_namespace".to_string(), "kube-system".to_string()),
+/// ]);
+///
+/// let rules = vec![
+///     RelabelConfig::new(RelabelAction::Replace)
+///         .with_source_labels(&["__meta_kubernetes_namespace"])
+///         .with_target_label("namespace"),
+///     RelabelConfig::new(RelabelAction::Drop)
+///         .with_source_labels(&["namespace"])
+///         .with_regex("kube-.*"),
+/// ];
+///
+/// let relabeling = relabel::relabel(&labels, &rules).unwrap();
+///
+/// assert_eq!(relabeling.steps()[0].labels().get("namespace").unwrap(), "kube-system");
+/// assert_eq!(relabeling.dropped_by().unwrap().index(), 1);
+/// ```
+pub fn relabel(
+    labels: &HashMap<String, String>,
+    configs: &[RelabelConfig],
+) -> Result<Relabeling, Error> {
+    let input: BTreeMap<String, String> = labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    let mut current = input.clone();
+    let mut steps = vec![];
+
+    for (index, config) in configs.iter().enumerate() {
+        let dropped = !apply(config, &mut current)?;
+
+        steps.push(RelabelStep {
+            index,
+            config: config.clone(),
+            labels: current.clone(),
+            dropped,
+        });
+
+        if dropped {
+            break;
+        }
+    }
+
+    Ok(Relabeling { input, steps })
+}
+
+/// Explain why a target was dropped by applying the relabeling rules of its scrape
+/// configuration to the discovered labels. The scrape configuration is identified by the
+/// `job` label.
+pub fn explain_dropped(
+    config: &PrometheusConfig,
+    target: &DroppedTarget,
+) -> Result<Relabeling, Error> {
+    let job = target.discovered_labels().get("job").ok_or_else(|| {
+        invalid(String::from(
+            "the dropped target has no 'job' label to identify its scrape configuration",
+        ))
+    })?;
+
+    let scrape_config = config.scrape_config(job).ok_or_else(|| {
+        invalid(format!(
+            "the configuration does not contain a scrape configuration for job '{}'",
+            job
+        ))
+    })?;
+
+    relabel(target.discovered_labels(), scrape_config.relabel_configs())
+}
+
+fn invalid(message: String) -> Error {
+    Error::InvalidFunctionArgument(InvalidFunctionArgument { message })
+}
+
+// Apply a single rule in place, returning false if the label set is dropped.
+fn apply(config: &RelabelConfig, labels: &mut BTreeMap<String, String>) -> Result<bool, Error> {
+    let action = config.action();
+
+    let regex = Regex::new(&format!("^(?:{})$", config.regex())).map_err(|e| {
+        invalid(format!(
+            "the regex of a '{}' rule is invalid: {}",
+            action, e
+        ))
+    })?;
+
+    let value = config
+        .source_labels()
+        .iter()
+        .map(|name| labels.get(name).map(|v| v.as_str()).unwrap_or(""))
+        .collect::<Vec<&str>>()
+        .join(config.separator());
+
+    let target_label = || {
+        config
+            .target_label()
+            .ok_or_else(|| invalid(format!("a '{}' rule requires a target label", action)))
+    };
+
+    match action {
+        RelabelAction::Replace => {
+            let target = target_label()?;
+
+            if let Some(captures) = regex.captures(&value) {
+                let mut name = String::new();
+                captures.expand(target, &mut name);
+
+                if is_label_name(&name) {
+                    let mut result = String::new();
+                    captures.expand(config.replacement(), &mut result);
+                    set(labels, name, result);
+                }
+            }
+        }
+        RelabelAction::Lowercase => set(labels, target_label()?.to_string(), value.to_lowercase()),
+        RelabelAction::Uppercase => set(labels, target_label()?.to_string(), value.to_uppercase()),
+        RelabelAction::Keep => return Ok(regex.is_match(&value)),
+        RelabelAction::Drop => return Ok(!regex.is_match(&value)),
+        RelabelAction::KeepEqual => {
+            let target = target_label()?;
+            return Ok(labels.get(target).map(|v| v.as_str()).unwrap_or("") == value);
+        }
+        RelabelAction::DropEqual => {
+            let target = target_label()?;
+            return Ok(labels.get(target).map(|v| v.as_str()).unwrap_or("") != value);
+        }
+        RelabelAction::HashMod => {
+            let target = target_label()?;
+
+            let modulus = config.modulus().filter(|m| *m > 0).ok_or_else(|| {
+                invalid(String::from("a 'hashmod' rule requires a positive modulus"))
+            })?;
+
+            // Prometheus uses the lower 64 bits of the MD5 hash in big-endian order.
+            let hash = md5::compute(value.as_bytes());
+            let mut sum = [0u8; 8];
+            sum.copy_from_slice(&hash.0[8..]);
+
+            set(
+                labels,
+                target.to_string(),
+                (u64::from_be_bytes(sum) % modulus).to_string(),
+            );
+        }
+        RelabelAction::LabelMap => {
+            let mapped: Vec<(String, String)> = labels
+                .iter()
+                .filter(|(name, _)| regex.is_match(name))
+                .map(|(name, value)| {
+                    let name = regex.replace_all(name, config.replacement());
+                    (name.to_string(), value.to_string())
+                })
+                .collect();
+
+            for (name, value) in mapped {
+                set(labels, name, value);
+            }
+        }
+        RelabelAction::LabelDrop => labels.retain(|name, _| !regex.is_match(name)),
+        RelabelAction::LabelKeep => labels.retain(|name, _| regex.is_match(name)),
+    }
+
+    Ok(true)
+}
+
+// Labels with empty values are equivalent to missing labels.
+fn set(labels: &mut BTreeMap<String, String>, name: String, value: String) {
+    match value.is_empty() {
+        true => labels.remove(&name),
+        false => labels.insert(name, value),
+    };
+}
+
+fn is_label_name(name: &str) -> bool {
+    let mut cursor = Cursor::new(name);
+    cursor.label_name().is_some() && cursor.rest().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> HashMap<String, String> {
+        HashMap::from([
+            ("__address__".to_string(), "10.0.0.1:8080".to_string()),
+            ("__meta_pod".to_string(), "api-7d4b".to_string()),
+            ("__meta_label_app".to_string(), "API".to_string()),
+            ("__meta_label_team".to_string(), "core".to_string()),
+            ("job".to_string(), "pods".to_string()),
+        ])
+    }
+
+    fn result(configs: &[RelabelConfig]) -> Option<BTreeMap<String, String>> {
+        relabel(&labels(), configs).unwrap().labels().cloned()
+    }
+
+    #[test]
+    fn test_replace() {
+        let labels = result(&[
+            RelabelConfig::new(RelabelAction::Replace)
+                .with_source_labels(&["__address__"])
+                .with_regex("([^:]+):\\d+")
+                .with_target_label("host"),
+            RelabelConfig::new(RelabelAction::Replace)
+                .with_source_labels(&["__meta_pod", "__meta_label_team"])
+                .with_separator("/")
+                .with_regex("(.+)-\\w+/(.+)")
+                .with_target_label("${2}_service")
+                .with_replacement("$1"),
+            RelabelConfig::new(RelabelAction::Replace)
+                .with_source_labels(&["__meta_missing"])
+                .with_target_label("job"),
+            RelabelConfig::new(RelabelAction::Replace)
+                .with_source_labels(&["job"])
+                .with_regex("nomatch")
+                .with_target_label("instance"),
+        ])
+        .unwrap();
+
+        assert_eq!(labels.get("host").unwrap(), "10.0.0.1");
+        assert_eq!(labels.get("core_service").unwrap(), "api");
+        assert!(!labels.contains_key("job"));
+        assert!(!labels.contains_key("instance"));
+    }
+
+    #[test]
+    fn test_keep_and_drop() {
+        let relabeling = relabel(
+            &labels(),
+            &[
+                RelabelConfig::new(RelabelAction::Keep)
+                    .with_source_labels(&["job"])
+                    .with_regex("pods|nodes"),
+                RelabelConfig::new(RelabelAction::Drop)
+                    .with_source_labels(&["__meta_label_team"])
+                    .with_regex("core"),
+                RelabelConfig::new(RelabelAction::LabelDrop).with_regex(".*"),
+            ],
+        )
+        .unwrap();
+
+        assert!(relabeling.is_dropped());
+        assert_eq!(relabeling.steps().len(), 2);
+        assert_eq!(relabeling.dropped_by().unwrap().index(), 1);
+        assert!(relabeling.labels().is_none());
+
+        assert!(result(&[RelabelConfig::new(RelabelAction::Keep)
+            .with_source_labels(&["job"])
+            .with_regex("pod")])
+        .is_none());
+    }
+
+    #[test]
+    fn test_keepequal_and_dropequal() {
+        let rule = |action| {
+            RelabelConfig::new(action)
+                .with_source_labels(&["__meta_label_team"])
+                .with_target_label("team")
+        };
+
+        assert!(result(&[rule(RelabelAction::KeepEqual)]).is_none());
+        assert!(result(&[rule(RelabelAction::DropEqual)]).is_some());
+    }
+
+    #[test]
+    fn test_hashmod() {
+        let labels = result(&[RelabelConfig::new(RelabelAction::HashMod)
+            .with_source_labels(&["__address__"])
+            .with_modulus(8)
+            .with_target_label("__tmp_hash")])
+        .unwrap();
+
+        // md5("10.0.0.1:8080") = 05ef642f508b1e5b76612ddbd5f89a4c
+        assert_eq!(
+            labels.get("__tmp_hash").unwrap(),
+            &(0x76612ddbd5f89a4cu64 % 8).to_string()
+        );
+
+        let missing = relabel(
+            &labels.into_iter().collect(),
+            &[RelabelConfig::new(RelabelAction::HashMod).with_target_label("x")],
+        );
+
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_label_actions() {
+        let labels = result(&[
+            RelabelConfig::new(RelabelAction::LabelMap).with_regex("__meta_label_(.+)"),
+            RelabelConfig::new(RelabelAction::Lowercase)
+                .with_source_labels(&["app"])
+                .with_target_label("app"),
+            RelabelConfig::new(RelabelAction::Uppercase)
+                .with_source_labels(&["team"])
+                .with_target_label("TEAM"),
+            RelabelConfig::new(RelabelAction::LabelDrop).with_regex("__meta_.*"),
+            RelabelConfig::new(RelabelAction::LabelKeep).with_regex("[a-zA-Z]+"),
+        ])
+        .unwrap();
+
+        let expected = BTreeMap::from([
+            ("app".to_string(), "api".to_string()),
+            ("team".to_string(), "core".to_string()),
+            ("TEAM".to_string(), "CORE".to_string()),
+            ("job".to_string(), "pods".to_string()),
+        ]);
+
+        assert_eq!(labels, expected);
+    }
+
+    #[test]
+    fn test_relabel_for_error() {
+        assert!(relabel(
+            &labels(),
+            &[RelabelConfig::new(RelabelAction::Keep).with_regex("(")]
+        )
+        .is_err());
+        assert!(relabel(&labels(), &[RelabelConfig::new(RelabelAction::Uppercase)]).is_err());
+    }
+
+    #[test]
+    fn test_explain_dropped() {
+        let config = PrometheusConfig::from_yaml(
+            "scrape_configs:\n- job_name: pods\n  relabel_configs:\n  - source_labels: [__meta_label_app]\n    regex: web\n    action: keep\n",
+        )
+        .unwrap();
+
+        let target = DroppedTarget {
+            discovered_labels: labels(),
+        };
+
+        let relabeling = explain_dropped(&config, &target).unwrap();
+
+        assert_eq!(
+            relabeling.dropped_by().unwrap().config().action(),
+            RelabelAction::Keep
+        );
+    }
+}
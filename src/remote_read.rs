@@ -0,0 +1,728 @@
+//! A reader for the Prometheus [remote read protocol](https://prometheus.io/docs/prometheus/latest/querying/remote_read_api/)
+//! that retrieves raw samples with their exact timestamps, as opposed to the step-aligned,
+//! evaluated data returned by [crate::Client::query_range].
+//!
+//! ```rust
+//! use prometheus_http_query::remote_read::{Reader, ResponseType};
+//! use prometheus_http_query::{Error, Selector};
+//! use std::convert::TryFrom;
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let reader = Reader::try_from("http://127.0.0.1:9090/api/v1/read")?
+//!         .response_type(ResponseType::StreamedXorChunks);
+//!
+//!     let s = Selector::new()
+//!         .metric("up")
+//!         .with("job", "prometheus");
+//!
+//!     let series = reader.read(&s, 1623345960000, 1623841309000).await?;
+//!
+//!     for s in &series {
+//!         println!("{:?}: {} samples", s.metric(), s.samples().len());
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::error::{Error, InvalidRemoteReadResponse};
+use crate::proto::v1;
+use crate::selector::Selector;
+use crate::util::Label;
+use prost::Message;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use url::Url;
+
+const STREAMED_CONTENT_TYPE: &str = "application/x-streamed-protobuf";
+
+/// Possible response types of the remote read protocol.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ResponseType {
+    /// All samples are returned in a single snappy-compressed message. Supported by all servers.
+    Samples,
+    /// Series are streamed as XOR-encoded chunks (just as they are stored in the TSDB),
+    /// which requires less memory on the server. Supported by Prometheus >= v2.13.
+    /// Servers that do not support this response type fall back to [ResponseType::Samples].
+    StreamedXorChunks,
+}
+
+/// A single time series containing raw samples as read via remote read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawSeries {
+    pub(crate) metric: HashMap<String, String>,
+    pub(crate) samples: Vec<RawSample>,
+}
+
+impl RawSeries {
+    /// Returns a reference to the set of labels (+ metric name)
+    /// of this time series.
+    pub fn metric(&self) -> &HashMap<String, String> {
+        &self.metric
+    }
+
+    /// Returns a reference to the set of samples of this time series.
+    pub fn samples(&self) -> &[RawSample] {
+        &self.samples
+    }
+}
+
+/// A single raw data point.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RawSample {
+    pub(crate) timestamp: i64,
+    pub(crate) value: f64,
+}
+
+impl RawSample {
+    /// Returns the exact timestamp of this sample as Unix timestamp in milliseconds.
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Returns the value contained in this sample.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+/// A reader that sends remote read requests, decodes the responses and returns raw series.
+#[derive(Debug, Clone)]
+pub struct Reader {
+    pub(crate) client: reqwest::Client,
+    pub(crate) url: String,
+    pub(crate) response_type: ResponseType,
+}
+
+impl std::convert::TryFrom<&str> for Reader {
+    type Error = crate::error::Error;
+
+    /// Create a Reader that sends requests to the full URL of a remote read endpoint,
+    /// e.g. `http://127.0.0.1:9090/api/v1/read`.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::remote_read::Reader;
+    /// use std::convert::TryFrom;
+    ///
+    /// let reader = Reader::try_from("http://127.0.0.1:9090/api/v1/read");
+    /// assert!(reader.is_ok());
+    /// ```
+    fn try_from(url: &str) -> Result<Self, Self::Error> {
+        Reader::from(reqwest::Client::new(), url)
+    }
+}
+
+impl Reader {
+    /// Create a Reader from a custom [reqwest::Client] and the full URL of a
+    /// remote read endpoint.
+    pub fn from(client: reqwest::Client, url: &str) -> Result<Self, Error> {
+        let url = Url::parse(url).map_err(Error::UrlParse)?.to_string();
+        Ok(Reader {
+            client,
+            url,
+            response_type: ResponseType::Samples,
+        })
+    }
+
+    /// Select the response type that is requested from the server (defaults to [ResponseType::Samples]).
+    pub fn response_type(mut self, response_type: ResponseType) -> Self {
+        self.response_type = response_type;
+        self
+    }
+
+    /// Read all raw samples of the time series that match a [Selector] within a time range.
+    /// `start` and `end` are Unix timestamps in milliseconds and both inclusive.
+    ///
+    /// Only the metric name and label matchers of the [Selector] are taken into account,
+    /// a range, offset or @ modifier is ignored.
+    pub async fn read(
+        &self,
+        selector: &Selector<'_>,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<RawSeries>, Error> {
+        if selector.labels.is_none() && selector.metric.is_none() {
+            return Err(Error::IllegalTimeSeriesSelector);
+        }
+
+        let accepted_response_types = match self.response_type {
+            ResponseType::Samples => vec![0],
+            ResponseType::StreamedXorChunks => vec![1, 0],
+        };
+
+        let request = v1::ReadRequest {
+            queries: vec![v1::Query {
+                start_timestamp_ms: start,
+                end_timestamp_ms: end,
+                matchers: matchers(selector),
+            }],
+            accepted_response_types,
+        };
+
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&request.encode_to_vec())
+            .map_err(Error::Snappy)?;
+
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_ENCODING, "snappy")
+            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+            .header("X-Prometheus-Remote-Read-Version", "0.1.0")
+            .body(body)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?
+            .error_for_status()
+            .map_err(Error::Reqwest)?;
+
+        let streamed = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.starts_with(STREAMED_CONTENT_TYPE))
+            .unwrap_or(false);
+
+        let body = response.bytes().await.map_err(Error::Reqwest)?;
+
+        if streamed {
+            decode_streamed_response(&body)
+        } else {
+            decode_samples_response(&body)
+        }
+    }
+}
+
+// Convert the metric name and label matchers of a selector to remote read label matchers.
+fn matchers(selector: &Selector<'_>) -> Vec<v1::LabelMatcher> {
+    let mut matchers = vec![];
+
+    if let Some(metric) = selector.metric {
+        matchers.push(v1::LabelMatcher {
+            r#type: 0,
+            name: String::from("__name__"),
+            value: metric.to_string(),
+        });
+    }
+
+    if let Some(labels) = &selector.labels {
+        for label in labels {
+            let (kind, (name, value)) = match label {
+                Label::With(pair) => (0, pair),
+                Label::Without(pair) => (1, pair),
+                Label::Matches(pair) => (2, pair),
+                Label::Clashes(pair) => (3, pair),
+            };
+            matchers.push(v1::LabelMatcher {
+                r#type: kind,
+                name: name.to_string(),
+                value: value.to_string(),
+            });
+        }
+    }
+
+    matchers
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidRemoteReadResponse(InvalidRemoteReadResponse {
+        message: message.to_string(),
+    })
+}
+
+fn to_metric(labels: Vec<v1::Label>) -> HashMap<String, String> {
+    labels.into_iter().map(|l| (l.name, l.value)).collect()
+}
+
+// Decode a snappy-compressed `ReadResponse` message.
+fn decode_samples_response(body: &[u8]) -> Result<Vec<RawSeries>, Error> {
+    let raw = snap::raw::Decoder::new()
+        .decompress_vec(body)
+        .map_err(Error::Snappy)?;
+
+    let response = v1::ReadResponse::decode(raw.as_slice()).map_err(Error::ProtobufDecode)?;
+
+    let series = response
+        .results
+        .into_iter()
+        .flat_map(|r| r.timeseries)
+        .map(|ts| RawSeries {
+            metric: to_metric(ts.labels),
+            samples: ts
+                .samples
+                .into_iter()
+                .map(|s| RawSample {
+                    timestamp: s.timestamp,
+                    value: s.value,
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(series)
+}
+
+// Decode a stream of `ChunkedReadResponse` messages. Each frame consists of the uvarint-encoded
+// size of the message, the CRC32 checksum (Castagnoli) of the message and the message itself.
+// The chunks of a single series may be spread across consecutive frames.
+fn decode_streamed_response(mut body: &[u8]) -> Result<Vec<RawSeries>, Error> {
+    let mut result: Vec<(Vec<v1::Label>, RawSeries)> = vec![];
+
+    while !body.is_empty() {
+        let size = prost::encoding::decode_varint(&mut body).map_err(Error::ProtobufDecode)?;
+        let size = usize::try_from(size).map_err(|_| invalid("a frame is too large"))?;
+        let length = size
+            .checked_add(4)
+            .ok_or_else(|| invalid("a frame is too large"))?;
+
+        if body.len() < length {
+            return Err(invalid("a frame is truncated"));
+        }
+
+        let (checksum, rest) = body.split_at(4);
+        let (message, rest) = rest.split_at(size);
+        body = rest;
+
+        let checksum = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+
+        if crc32c::crc32c(message) != checksum {
+            return Err(invalid("the checksum of a frame does not match"));
+        }
+
+        let response = v1::ChunkedReadResponse::decode(message).map_err(Error::ProtobufDecode)?;
+
+        for series in response.chunked_series {
+            let mut samples = vec![];
+
+            for chunk in &series.chunks {
+                if chunk.r#type != 1 {
+                    return Err(invalid(&format!(
+                        "chunk encoding {} is not supported, only XOR chunks (float samples) can be decoded",
+                        chunk.r#type
+                    )));
+                }
+                samples.extend(decode_xor_chunk(&chunk.data)?);
+            }
+
+            match result.last_mut() {
+                Some((labels, last)) if *labels == series.labels => last.samples.extend(samples),
+                _ => {
+                    let metric = to_metric(series.labels.clone());
+                    result.push((series.labels, RawSeries { metric, samples }));
+                }
+            }
+        }
+    }
+
+    Ok(result.into_iter().map(|(_, series)| series).collect())
+}
+
+// Reads single bits and groups of bits from a byte slice (most significant bit first).
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn read_bit(&mut self) -> Result<bool, Error> {
+        let byte = self
+            .data
+            .get(self.position / 8)
+            .ok_or_else(|| invalid("a chunk is truncated"))?;
+        let bit = byte & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+        Ok(bit)
+    }
+
+    fn read_bits(&mut self, count: u8) -> Result<u64, Error> {
+        let mut bits = 0u64;
+        for _ in 0..count {
+            bits = (bits << 1) | self.read_bit()? as u64;
+        }
+        Ok(bits)
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bits(8)? as u8)
+    }
+
+    fn read_uvarint(&mut self) -> Result<u64, Error> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("a varint in a chunk overflows"))
+    }
+
+    fn read_varint(&mut self) -> Result<i64, Error> {
+        let raw = self.read_uvarint()?;
+        // Zig-zag decoding as done by Go's `binary.Varint`.
+        Ok((raw >> 1) as i64 ^ -((raw & 1) as i64))
+    }
+}
+
+// Decode a chunk in the XOR encoding of the Prometheus TSDB ("Gorilla" compression): the number
+// of samples (2 bytes), the first timestamp (varint) and value (64 bits), the second timestamp
+// as delta (uvarint), all further timestamps as delta of deltas and all values XOR'd with their
+// predecessor.
+fn decode_xor_chunk(data: &[u8]) -> Result<Vec<RawSample>, Error> {
+    if data.len() < 2 {
+        return Err(invalid("a chunk is truncated"));
+    }
+
+    let count = u16::from_be_bytes([data[0], data[1]]) as usize;
+
+    let mut reader = BitReader {
+        data: &data[2..],
+        position: 0,
+    };
+
+    let mut samples = Vec::with_capacity(count);
+
+    let mut timestamp = 0i64;
+    let mut delta = 0i64;
+    let mut value = 0u64;
+    let mut leading = 0u8;
+    let mut trailing = 0u8;
+
+    for i in 0..count {
+        match i {
+            0 => {
+                timestamp = reader.read_varint()?;
+                value = reader.read_bits(64)?;
+            }
+            1 => {
+                delta = reader.read_uvarint()? as i64;
+                timestamp += delta;
+                value = read_xor_value(&mut reader, value, &mut leading, &mut trailing)?;
+            }
+            _ => {
+                let mut prefix = 0u8;
+                for _ in 0..4 {
+                    prefix <<= 1;
+                    if !reader.read_bit()? {
+                        break;
+                    }
+                    prefix |= 1;
+                }
+
+                let size = match prefix {
+                    0b0 => 0,
+                    0b10 => 14,
+                    0b110 => 17,
+                    0b1110 => 20,
+                    _ => 64,
+                };
+
+                let delta_of_delta = match size {
+                    0 => 0,
+                    64 => reader.read_bits(64)? as i64,
+                    _ => {
+                        let mut bits = reader.read_bits(size)?;
+                        if bits > (1 << (size - 1)) {
+                            bits = bits.wrapping_sub(1 << size);
+                        }
+                        bits as i64
+                    }
+                };
+
+                delta += delta_of_delta;
+                timestamp += delta;
+                value = read_xor_value(&mut reader, value, &mut leading, &mut trailing)?;
+            }
+        }
+
+        samples.push(RawSample {
+            timestamp,
+            value: f64::from_bits(value),
+        });
+    }
+
+    Ok(samples)
+}
+
+fn read_xor_value(
+    reader: &mut BitReader,
+    value: u64,
+    leading: &mut u8,
+    trailing: &mut u8,
+) -> Result<u64, Error> {
+    // The value is unchanged.
+    if !reader.read_bit()? {
+        return Ok(value);
+    }
+
+    // Otherwise either reuse the previous number of leading and trailing zeros or read new ones.
+    if reader.read_bit()? {
+        *leading = reader.read_bits(5)? as u8;
+        let mut significant = reader.read_bits(6)? as u8;
+        if significant == 0 {
+            significant = 64;
+        }
+        *trailing = 64u8
+            .checked_sub(*leading + significant)
+            .ok_or_else(|| invalid("a value in a chunk is malformed"))?;
+    }
+
+    let significant = 64 - *leading - *trailing;
+    let bits = reader.read_bits(significant)?;
+
+    Ok(value ^ (bits << *trailing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Writes single bits and groups of bits to a byte vector (most significant bit first).
+    #[derive(Default)]
+    struct BitWriter {
+        data: Vec<u8>,
+        position: usize,
+    }
+
+    impl BitWriter {
+        fn write_bit(&mut self, bit: bool) {
+            if self.position.is_multiple_of(8) {
+                self.data.push(0);
+            }
+            if bit {
+                *self.data.last_mut().unwrap() |= 0x80 >> (self.position % 8);
+            }
+            self.position += 1;
+        }
+
+        fn write_bits(&mut self, bits: u64, count: u8) {
+            for i in (0..count).rev() {
+                self.write_bit(bits & (1 << i) != 0);
+            }
+        }
+
+        fn write_uvarint(&mut self, mut value: u64) {
+            while value >= 0x80 {
+                self.write_bits((value as u8 | 0x80) as u64, 8);
+                value >>= 7;
+            }
+            self.write_bits(value, 8);
+        }
+    }
+
+    // The XOR chunk encoding as implemented by the Prometheus TSDB.
+    fn encode_xor_chunk(samples: &[(i64, f64)]) -> Vec<u8> {
+        let mut w = BitWriter::default();
+        let (mut t, mut delta, mut v) = (0i64, 0i64, 0u64);
+        let (mut leading, mut trailing) = (0xffu8, 0u8);
+
+        for (i, (timestamp, value)) in samples.iter().enumerate() {
+            let bits = value.to_bits();
+            match i {
+                0 => {
+                    w.write_uvarint(((timestamp << 1) ^ (timestamp >> 63)) as u64);
+                    w.write_bits(bits, 64);
+                }
+                _ => {
+                    if i == 1 {
+                        w.write_uvarint((timestamp - t) as u64);
+                    } else {
+                        let dod = (timestamp - t) - delta;
+                        let fits = |n: u8| -((1 << (n - 1)) - 1) <= dod && dod <= 1 << (n - 1);
+                        if dod == 0 {
+                            w.write_bit(false);
+                        } else if fits(14) {
+                            w.write_bits(0b10, 2);
+                            w.write_bits(dod as u64, 14);
+                        } else if fits(17) {
+                            w.write_bits(0b110, 3);
+                            w.write_bits(dod as u64, 17);
+                        } else if fits(20) {
+                            w.write_bits(0b1110, 4);
+                            w.write_bits(dod as u64, 20);
+                        } else {
+                            w.write_bits(0b1111, 4);
+                            w.write_bits(dod as u64, 64);
+                        }
+                    }
+                    delta = timestamp - t;
+
+                    let xor = bits ^ v;
+                    if xor == 0 {
+                        w.write_bit(false);
+                    } else {
+                        w.write_bit(true);
+                        let new_leading = (xor.leading_zeros() as u8).min(31);
+                        let new_trailing = xor.trailing_zeros() as u8;
+                        if leading != 0xff && new_leading >= leading && new_trailing >= trailing {
+                            w.write_bit(false);
+                            w.write_bits(xor >> trailing, 64 - leading - trailing);
+                        } else {
+                            leading = new_leading;
+                            trailing = new_trailing;
+                            w.write_bit(true);
+                            w.write_bits(leading as u64, 5);
+                            let significant = 64 - leading - trailing;
+                            w.write_bits(significant as u64 % 64, 6);
+                            w.write_bits(xor >> trailing, significant);
+                        }
+                    }
+                }
+            }
+            t = *timestamp;
+            v = bits;
+        }
+
+        let mut chunk = (samples.len() as u16).to_be_bytes().to_vec();
+        chunk.extend(w.data);
+        chunk
+    }
+
+    fn samples() -> Vec<(i64, f64)> {
+        vec![
+            (1623345960000, 1.0),
+            (1623345975000, 1.0),
+            (1623345990000, 2.5),
+            (1623346005001, 2.5),
+            (1623346020000, -3.75),
+            (1623346035000, 1e100),
+            (1623346035500, 0.0),
+            (1623350000000, 42.0),
+            (1623450000000, f64::MAX),
+            (1633450000000, 7.0),
+        ]
+    }
+
+    #[test]
+    fn test_decode_xor_chunk() {
+        let chunk = encode_xor_chunk(&samples());
+
+        let result: Vec<(i64, f64)> = decode_xor_chunk(&chunk)
+            .unwrap()
+            .iter()
+            .map(|s| (s.timestamp(), s.value()))
+            .collect();
+
+        assert_eq!(result, samples());
+    }
+
+    #[test]
+    fn test_decode_xor_chunk_for_error() {
+        let chunk = encode_xor_chunk(&samples());
+
+        assert!(decode_xor_chunk(&chunk[..chunk.len() - 8]).is_err());
+    }
+
+    #[test]
+    fn test_decode_streamed_response() {
+        let labels = vec![v1::Label {
+            name: String::from("__name__"),
+            value: String::from("up"),
+        }];
+
+        let samples = samples();
+        let (one, two) = samples.split_at(4);
+
+        let mut body = vec![];
+
+        for part in [one, two] {
+            let message = v1::ChunkedReadResponse {
+                chunked_series: vec![v1::ChunkedSeries {
+                    labels: labels.clone(),
+                    chunks: vec![v1::Chunk {
+                        min_time_ms: part[0].0,
+                        max_time_ms: part[part.len() - 1].0,
+                        r#type: 1,
+                        data: encode_xor_chunk(part),
+                    }],
+                }],
+                query_index: 0,
+            }
+            .encode_to_vec();
+
+            prost::encoding::encode_varint(message.len() as u64, &mut body);
+            body.extend(crc32c::crc32c(&message).to_be_bytes());
+            body.extend(message);
+        }
+
+        let result = decode_streamed_response(&body).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].metric().get("__name__").unwrap(), "up");
+        assert_eq!(result[0].samples().len(), 10);
+
+        let last = body.len() - 1;
+        body[last] ^= 0xff;
+
+        assert!(decode_streamed_response(&body).is_err());
+
+        // A frame size that overflows must not panic.
+        let mut body = vec![];
+        prost::encoding::encode_varint(u64::MAX, &mut body);
+        body.extend([0; 8]);
+
+        assert!(matches!(
+            decode_streamed_response(&body),
+            Err(Error::InvalidRemoteReadResponse(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_samples_response() {
+        let message = v1::ReadResponse {
+            results: vec![v1::QueryResult {
+                timeseries: vec![v1::TimeSeries {
+                    labels: vec![v1::Label {
+                        name: String::from("__name__"),
+                        value: String::from("up"),
+                    }],
+                    samples: vec![v1::Sample {
+                        value: 1.0,
+                        timestamp: 1623345960123,
+                    }],
+                }],
+            }],
+        }
+        .encode_to_vec();
+
+        let body = snap::raw::Encoder::new().compress_vec(&message).unwrap();
+
+        let result = decode_samples_response(&body).unwrap();
+
+        assert_eq!(
+            result[0].samples(),
+            &[RawSample {
+                timestamp: 1623345960123,
+                value: 1.0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_matchers() {
+        let s = Selector::new()
+            .metric("http_requests_total")
+            .with("handler", "/api/comments")
+            .regex_match("job", ".*server")
+            .no_regex_match("status", "4..")
+            .without("env", "test");
+
+        let matchers = matchers(&s);
+        let result: Vec<(i32, &str)> = matchers
+            .iter()
+            .map(|m| (m.r#type, m.name.as_str()))
+            .collect();
+
+        assert_eq!(
+            result,
+            vec![
+                (0, "__name__"),
+                (0, "handler"),
+                (2, "job"),
+                (3, "status"),
+                (1, "env")
+            ]
+        );
+    }
+}
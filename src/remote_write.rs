@@ -0,0 +1,712 @@
+//! A sender for the Prometheus [remote write protocol](https://prometheus.io/docs/specs/remote_write_spec/)
+//! that pushes samples to Prometheus (started with `--web.enable-remote-write-receiver`) or any other
+//! compatible receiver like Mimir, Cortex or Thanos.
+//!
+//! ```rust
+//! use prometheus_http_query::remote_write::{Protocol, Sender, Series};
+//! use prometheus_http_query::Error;
+//! use std::convert::TryFrom;
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let sender = Sender::try_from("http://127.0.0.1:9090/api/v1/write")?
+//!         .protocol(Protocol::V1)
+//!         .max_samples_per_send(500);
+//!
+//!     let series = Series::new()
+//!         .metric("synthetic_requests_total")
+//!         .label("job", "backfill")
+//!         .sample(1623345960000, 1.0)
+//!         .sample(1623345975000, 3.0);
+//!
+//!     let summary = sender.send(&[series]).await?;
+//!
+//!     assert_eq!(summary.samples_rejected(), 0);
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::error::{Error, InvalidFunctionArgument};
+use crate::proto::{v1, v2};
+use prost::Message;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+use url::Url;
+
+const CONTENT_TYPE_V1: &str = "application/x-protobuf";
+const CONTENT_TYPE_V2: &str = "application/x-protobuf;proto=io.prometheus.write.v2.Request";
+const VERSION_HEADER: &str = "X-Prometheus-Remote-Write-Version";
+const SAMPLES_WRITTEN_HEADER: &str = "X-Prometheus-Remote-Write-Samples-Written";
+
+// The series (labels and a subset of their samples) that are sent in a single request.
+type Batch<'a> = Vec<(&'a BTreeMap<String, String>, Vec<(i64, f64)>)>;
+
+/// Versions of the remote write protocol.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Protocol {
+    /// Remote write 1.0, supported by all receivers.
+    V1,
+    /// Remote write 2.0, which deduplicates label names and values in a symbol table.
+    V2,
+}
+
+impl Protocol {
+    fn content_type(&self) -> &'static str {
+        match self {
+            Protocol::V1 => CONTENT_TYPE_V1,
+            Protocol::V2 => CONTENT_TYPE_V2,
+        }
+    }
+
+    fn version(&self) -> &'static str {
+        match self {
+            Protocol::V1 => "0.1.0",
+            Protocol::V2 => "2.0.0",
+        }
+    }
+}
+
+/// A time series, i.e. a set of labels (+ metric name) and samples, to be sent to a receiver.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Series {
+    pub(crate) labels: BTreeMap<String, String>,
+    pub(crate) samples: Vec<(i64, f64)>,
+}
+
+impl Series {
+    /// Return an empty [Series] to build on.
+    pub fn new() -> Self {
+        Series::default()
+    }
+
+    /// Set the metric name of this [Series], i.e. the `__name__` label.
+    pub fn metric(self, metric: &str) -> Self {
+        self.label("__name__", metric)
+    }
+
+    /// Add a label to this [Series]. An existing label of the same name is overwritten.
+    pub fn label(mut self, name: &str, value: &str) -> Self {
+        self.labels.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Add a sample to this [Series]. The timestamp is a Unix timestamp in milliseconds.
+    pub fn sample(mut self, timestamp: i64, value: f64) -> Self {
+        self.samples.push((timestamp, value));
+        self
+    }
+
+    /// Add multiple samples (pairs of Unix timestamps in milliseconds and values) to this [Series].
+    pub fn extend(mut self, samples: impl IntoIterator<Item = (i64, f64)>) -> Self {
+        self.samples.extend(samples);
+        self
+    }
+
+    /// Get the set of labels (+ metric name) of this series.
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    /// Get the samples of this series.
+    pub fn samples(&self) -> &[(i64, f64)] {
+        &self.samples
+    }
+}
+
+impl From<HashMap<String, String>> for Series {
+    /// Create a [Series] from a set of labels, e.g. as returned by [crate::response::RangeVector::metric].
+    fn from(labels: HashMap<String, String>) -> Self {
+        Series {
+            labels: labels.into_iter().collect(),
+            samples: vec![],
+        }
+    }
+}
+
+/// A summary of the samples that were written and rejected by the receiver in the
+/// course of [Sender::send].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WriteSummary {
+    pub(crate) samples_written: usize,
+    pub(crate) samples_rejected: usize,
+    pub(crate) rejections: Vec<Rejection>,
+}
+
+impl WriteSummary {
+    /// Get the number of samples that the receiver accepted.
+    pub fn samples_written(&self) -> usize {
+        self.samples_written
+    }
+
+    /// Get the number of samples that were rejected or could not be delivered.
+    pub fn samples_rejected(&self) -> usize {
+        self.samples_rejected
+    }
+
+    /// Get details about every request that was (partially) rejected.
+    pub fn rejections(&self) -> &[Rejection] {
+        &self.rejections
+    }
+
+    fn merge(&mut self, other: WriteSummary) {
+        self.samples_written += other.samples_written;
+        self.samples_rejected += other.samples_rejected;
+        self.rejections.extend(other.rejections);
+    }
+}
+
+/// A single request whose samples were (partially) rejected by the receiver, or that
+/// could not be delivered even after retrying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejection {
+    pub(crate) status: Option<u16>,
+    pub(crate) message: String,
+    pub(crate) samples: usize,
+    pub(crate) attempts: usize,
+}
+
+impl Rejection {
+    /// Get the HTTP status code of the last response, if a response was received at all.
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// Get the error message as returned by the receiver, or the reason why the request failed.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the number of rejected samples.
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Get the number of attempts that were made to deliver the request.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+}
+
+/// A sender that batches and shards series, encodes them with protobuf and snappy and
+/// sends them to a remote write receiver.
+///
+/// Samples of a series are always sent in order of their timestamps, because each series is
+/// assigned to a single shard and the requests of a shard are sent one after another. Requests
+/// that fail with a `5xx` status code (and optionally `429 Too Many Requests`) or due to network
+/// errors are retried with exponential backoff, other failures are reported as [Rejection]s.
+#[derive(Debug, Clone)]
+pub struct Sender {
+    pub(crate) client: reqwest::Client,
+    pub(crate) url: String,
+    pub(crate) protocol: Protocol,
+    pub(crate) max_samples_per_send: usize,
+    pub(crate) shards: usize,
+    pub(crate) max_retries: usize,
+    pub(crate) min_backoff: Duration,
+    pub(crate) max_backoff: Duration,
+    pub(crate) retry_on_rate_limit: bool,
+}
+
+impl std::convert::TryFrom<&str> for Sender {
+    type Error = crate::error::Error;
+
+    /// Create a Sender that sends requests to the full URL of a remote write endpoint,
+    /// e.g. `http://127.0.0.1:9090/api/v1/write`.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::remote_write::Sender;
+    /// use std::convert::TryFrom;
+    ///
+    /// let sender = Sender::try_from("http://127.0.0.1:9090/api/v1/write");
+    /// assert!(sender.is_ok());
+    /// ```
+    fn try_from(url: &str) -> Result<Self, Self::Error> {
+        Sender::from(reqwest::Client::new(), url)
+    }
+}
+
+impl Sender {
+    /// Create a Sender from a custom [reqwest::Client] and the full URL of a
+    /// remote write endpoint.
+    pub fn from(client: reqwest::Client, url: &str) -> Result<Self, Error> {
+        let url = Url::parse(url).map_err(Error::UrlParse)?.to_string();
+        Ok(Sender {
+            client,
+            url,
+            protocol: Protocol::V1,
+            max_samples_per_send: 2000,
+            shards: 1,
+            max_retries: 10,
+            min_backoff: Duration::from_millis(30),
+            max_backoff: Duration::from_secs(5),
+            retry_on_rate_limit: false,
+        })
+    }
+
+    /// Select the version of the remote write protocol (defaults to [Protocol::V1]).
+    pub fn protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Set the maximum number of samples per request (defaults to 2000).
+    pub fn max_samples_per_send(mut self, max: usize) -> Self {
+        self.max_samples_per_send = max.max(1);
+        self
+    }
+
+    /// Set the number of shards, i.e. the number of requests that are sent concurrently (defaults to 1).
+    pub fn shards(mut self, shards: usize) -> Self {
+        self.shards = shards.max(1);
+        self
+    }
+
+    /// Set the maximum number of retries per request (defaults to 10).
+    pub fn max_retries(mut self, max: usize) -> Self {
+        self.max_retries = max;
+        self
+    }
+
+    /// Set the initial and the maximum time to wait between retries (defaults to 30ms and 5s).
+    /// The backoff is doubled on each retry unless the receiver sends a `Retry-After` header.
+    pub fn backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = max.max(min);
+        self
+    }
+
+    /// Retry requests that are rejected with `429 Too Many Requests` instead of
+    /// reporting them as rejected (defaults to `false`).
+    pub fn retry_on_rate_limit(mut self, retry: bool) -> Self {
+        self.retry_on_rate_limit = retry;
+        self
+    }
+
+    /// Send a set of series to the receiver and return a summary of written and rejected samples.
+    ///
+    /// An error is returned if a series is invalid (e.g. because it has no labels), so
+    /// nothing is sent at all. Failures of individual requests do not abort sending
+    /// the remaining requests, but are reported in the [WriteSummary] instead.
+    pub async fn send(&self, series: &[Series]) -> Result<WriteSummary, Error> {
+        for s in series {
+            if s.labels.is_empty() || s.labels.keys().any(|name| name.is_empty()) {
+                return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                    message: String::from(
+                        "series sent via remote write must have at least one label and label names cannot be empty",
+                    ),
+                }));
+            }
+        }
+
+        let mut requests = vec![];
+
+        for batches in self.batches(series) {
+            let mut shard = vec![];
+            for batch in batches {
+                let samples = batch.iter().map(|(_, s)| s.len()).sum();
+                shard.push((self.encode(&batch)?, samples));
+            }
+            requests.push(shard);
+        }
+
+        let shards = requests.into_iter().map(|shard| async move {
+            let mut summary = WriteSummary::default();
+            for (body, samples) in shard {
+                summary.merge(self.send_request(body, samples).await);
+            }
+            summary
+        });
+
+        let mut summary = WriteSummary::default();
+
+        for shard_summary in futures_util::future::join_all(shards).await {
+            summary.merge(shard_summary);
+        }
+
+        Ok(summary)
+    }
+
+    // Assign each series to a shard by the hash of its labels and split the samples
+    // of each shard into batches of at most `max_samples_per_send` samples.
+    fn batches<'a>(&self, series: &'a [Series]) -> Vec<Vec<Batch<'a>>> {
+        let mut shards: Vec<Vec<&Series>> = vec![vec![]; self.shards];
+
+        for s in series {
+            let mut hasher = DefaultHasher::new();
+            s.labels.hash(&mut hasher);
+            let index = (hasher.finish() % self.shards as u64) as usize;
+            shards[index].push(s);
+        }
+
+        shards
+            .into_iter()
+            .map(|shard| {
+                let mut batches = vec![];
+                let mut batch = vec![];
+                let mut size = 0;
+
+                for s in shard {
+                    let mut samples = s.samples.clone();
+                    samples.sort_by_key(|(t, _)| *t);
+
+                    let mut samples = samples.as_slice();
+
+                    while !samples.is_empty() {
+                        let (head, tail) =
+                            samples.split_at(samples.len().min(self.max_samples_per_send - size));
+                        batch.push((&s.labels, head.to_vec()));
+                        size += head.len();
+                        samples = tail;
+
+                        if size == self.max_samples_per_send {
+                            batches.push(std::mem::take(&mut batch));
+                            size = 0;
+                        }
+                    }
+                }
+
+                if !batch.is_empty() {
+                    batches.push(batch);
+                }
+
+                batches
+            })
+            .collect()
+    }
+
+    // Encode a batch of series as protobuf message of the configured protocol version
+    // and compress it with snappy (block format, as required by the specification).
+    fn encode(&self, batch: &Batch) -> Result<Vec<u8>, Error> {
+        let message = match self.protocol {
+            Protocol::V1 => {
+                let timeseries = batch
+                    .iter()
+                    .map(|(labels, samples)| v1::TimeSeries {
+                        labels: labels
+                            .iter()
+                            .map(|(name, value)| v1::Label {
+                                name: name.to_string(),
+                                value: value.to_string(),
+                            })
+                            .collect(),
+                        samples: samples
+                            .iter()
+                            .map(|(timestamp, value)| v1::Sample {
+                                value: *value,
+                                timestamp: *timestamp,
+                            })
+                            .collect(),
+                    })
+                    .collect();
+
+                v1::WriteRequest { timeseries }.encode_to_vec()
+            }
+            Protocol::V2 => {
+                // The first symbol must always be the empty string.
+                let mut symbols = vec![String::new()];
+                let mut refs: HashMap<String, u32> = HashMap::new();
+                refs.insert(String::new(), 0);
+
+                let mut symbol_ref = |symbol: &str| -> u32 {
+                    if let Some(r) = refs.get(symbol) {
+                        return *r;
+                    }
+                    let r = symbols.len() as u32;
+                    symbols.push(symbol.to_string());
+                    refs.insert(symbol.to_string(), r);
+                    r
+                };
+
+                let mut timeseries = vec![];
+
+                for (labels, samples) in batch {
+                    let mut labels_refs = vec![];
+                    for (name, value) in labels.iter() {
+                        labels_refs.push(symbol_ref(name));
+                        labels_refs.push(symbol_ref(value));
+                    }
+
+                    timeseries.push(v2::TimeSeries {
+                        labels_refs,
+                        samples: samples
+                            .iter()
+                            .map(|(timestamp, value)| v2::Sample {
+                                value: *value,
+                                timestamp: *timestamp,
+                            })
+                            .collect(),
+                        metadata: Some(v2::Metadata::default()),
+                    });
+                }
+
+                v2::Request {
+                    symbols,
+                    timeseries,
+                }
+                .encode_to_vec()
+            }
+        };
+
+        snap::raw::Encoder::new()
+            .compress_vec(&message)
+            .map_err(Error::Snappy)
+    }
+
+    // Send a single request, retrying it as long as the failure is recoverable.
+    async fn send_request(&self, body: Vec<u8>, samples: usize) -> WriteSummary {
+        let mut backoff = self.min_backoff;
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            let result = self
+                .client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_ENCODING, "snappy")
+                .header(reqwest::header::CONTENT_TYPE, self.protocol.content_type())
+                .header(
+                    reqwest::header::USER_AGENT,
+                    concat!("prometheus-http-query/", env!("CARGO_PKG_VERSION")),
+                )
+                .header(VERSION_HEADER, self.protocol.version())
+                .body(body.clone())
+                .send()
+                .await;
+
+            let (rejection, retry_after) = match result {
+                Ok(response) => {
+                    let status = response.status();
+
+                    let written = response
+                        .headers()
+                        .get(SAMPLES_WRITTEN_HEADER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse::<usize>().ok());
+
+                    let retry_after = response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .map(Duration::from_secs);
+
+                    if status.is_success() {
+                        let written = written.unwrap_or(samples).min(samples);
+                        let mut summary = WriteSummary {
+                            samples_written: written,
+                            samples_rejected: samples - written,
+                            rejections: vec![],
+                        };
+                        if written < samples {
+                            summary.rejections.push(Rejection {
+                                status: Some(status.as_u16()),
+                                message: format!(
+                                    "the receiver only wrote {} of {} samples",
+                                    written, samples
+                                ),
+                                samples: samples - written,
+                                attempts,
+                            });
+                        }
+                        return summary;
+                    }
+
+                    let retryable = status.is_server_error()
+                        || (status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                            && self.retry_on_rate_limit);
+
+                    let message = response.text().await.unwrap_or_default();
+
+                    let written = written.unwrap_or(0).min(samples);
+
+                    let rejection = Rejection {
+                        status: Some(status.as_u16()),
+                        message: message.trim().to_string(),
+                        samples: samples - written,
+                        attempts,
+                    };
+
+                    if !retryable {
+                        return WriteSummary {
+                            samples_written: written,
+                            samples_rejected: samples - written,
+                            rejections: vec![rejection],
+                        };
+                    }
+
+                    (rejection, retry_after)
+                }
+                Err(e) => {
+                    let rejection = Rejection {
+                        status: None,
+                        message: e.to_string(),
+                        samples,
+                        attempts,
+                    };
+                    (rejection, None)
+                }
+            };
+
+            if attempts > self.max_retries {
+                return WriteSummary {
+                    samples_written: samples - rejection.samples,
+                    samples_rejected: rejection.samples,
+                    rejections: vec![rejection],
+                };
+            }
+
+            tokio::time::sleep(retry_after.unwrap_or(backoff)).await;
+
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server;
+    use std::convert::TryFrom;
+
+    async fn receiver(statuses: Vec<u16>) -> (String, test_server::Requests) {
+        let (url, received) = test_server::start_with_statuses(statuses, "message").await;
+        (format!("{}/api/v1/write", url), received)
+    }
+
+    fn decode_v1(body: &[u8]) -> v1::WriteRequest {
+        let raw = snap::raw::Decoder::new().decompress_vec(body).unwrap();
+        v1::WriteRequest::decode(raw.as_slice()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send_v1_batches() {
+        let (url, received) = receiver(vec![204]).await;
+
+        let sender = Sender::try_from(url.as_str())
+            .unwrap()
+            .max_samples_per_send(2);
+
+        let one = Series::new()
+            .metric("some_metric")
+            .label("job", "test")
+            .sample(3000, 3.0)
+            .sample(1000, 1.0)
+            .sample(2000, 2.0);
+
+        let two = Series::new().metric("other_metric").sample(1000, 5.0);
+
+        let summary = sender.send(&[one, two]).await.unwrap();
+
+        assert_eq!(summary.samples_written(), 4);
+        assert_eq!(summary.samples_rejected(), 0);
+
+        let received = received.lock().unwrap();
+
+        assert_eq!(received.len(), 2);
+        assert!(received[0].headers.contains("content-encoding: snappy"));
+        assert!(received[0]
+            .headers
+            .contains("x-prometheus-remote-write-version: 0.1.0"));
+
+        let first = decode_v1(&received[0].body);
+
+        assert_eq!(first.timeseries.len(), 1);
+        assert_eq!(first.timeseries[0].labels[0].name, "__name__");
+        assert_eq!(first.timeseries[0].labels[1].name, "job");
+        assert_eq!(
+            first.timeseries[0]
+                .samples
+                .iter()
+                .map(|s| s.timestamp)
+                .collect::<Vec<i64>>(),
+            vec![1000, 2000]
+        );
+
+        let second = decode_v1(&received[1].body);
+
+        assert_eq!(second.timeseries.len(), 2);
+        assert_eq!(second.timeseries[0].samples[0].timestamp, 3000);
+    }
+
+    #[tokio::test]
+    async fn test_send_v2_symbols() {
+        let (url, received) = receiver(vec![204]).await;
+
+        let sender = Sender::try_from(url.as_str())
+            .unwrap()
+            .protocol(Protocol::V2);
+
+        let one = Series::new()
+            .metric("some_metric")
+            .label("job", "test")
+            .sample(1000, 1.0);
+
+        let two = Series::new()
+            .metric("other_metric")
+            .label("job", "test")
+            .sample(1000, 2.0);
+
+        sender.send(&[one, two]).await.unwrap();
+
+        let received = received.lock().unwrap();
+        let raw = snap::raw::Decoder::new()
+            .decompress_vec(&received[0].body)
+            .unwrap();
+        let request = v2::Request::decode(raw.as_slice()).unwrap();
+
+        assert_eq!(
+            request.symbols,
+            vec!["", "__name__", "some_metric", "job", "test", "other_metric"]
+        );
+        assert_eq!(request.timeseries[1].labels_refs, vec![1, 5, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_recoverable_errors() {
+        let (url, received) = receiver(vec![503, 500, 204]).await;
+
+        let sender = Sender::try_from(url.as_str())
+            .unwrap()
+            .backoff(Duration::from_millis(1), Duration::from_millis(2));
+
+        let series = Series::new().metric("some_metric").sample(1000, 1.0);
+
+        let summary = sender.send(&[series]).await.unwrap();
+
+        assert_eq!(summary.samples_written(), 1);
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_rejected_samples() {
+        let (url, received) = receiver(vec![400]).await;
+
+        let sender = Sender::try_from(url.as_str()).unwrap();
+
+        let series = Series::new()
+            .metric("some_metric")
+            .sample(1000, 1.0)
+            .sample(2000, 1.0);
+
+        let summary = sender.send(&[series]).await.unwrap();
+
+        assert_eq!(summary.samples_written(), 0);
+        assert_eq!(summary.samples_rejected(), 2);
+        assert_eq!(summary.rejections()[0].status(), Some(400));
+        assert_eq!(summary.rejections()[0].message(), "message");
+        assert_eq!(summary.rejections()[0].attempts(), 1);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_invalid_series() {
+        let sender = Sender::try_from("http://127.0.0.1:9090/api/v1/write").unwrap();
+
+        let series = Series::new().sample(1000, 1.0);
+
+        assert!(sender.send(&[series]).await.is_err());
+    }
+}
@@ -1,12 +1,17 @@
 //! All types that may be returned as part of return types from [crate::Client] methods.
+use crate::config::PrometheusConfig;
+use crate::error::Error;
+use crate::exposition;
 use crate::util::{AlertState, RuleHealth, TargetHealth};
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::ser::{SerializeStruct, SerializeTuple};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::sync::OnceLock;
 use time::OffsetDateTime;
 use url::Url;
 
-mod de {
+pub(crate) mod de {
     use serde::{Deserialize, Deserializer};
     use std::str::FromStr;
     use time::format_description::well_known::Rfc3339;
@@ -40,111 +45,1326 @@ mod de {
         OffsetDateTime::parse(&raw, &Rfc3339)
             .map_err(|e| serde::de::Error::custom(format!("error parsing '{}': {}", raw, e)))
     }
+
+    pub(crate) fn deserialize_rfc3339_opt<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<OffsetDateTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(raw) => OffsetDateTime::parse(&raw, &Rfc3339)
+                .map(Some)
+                .map_err(|e| serde::de::Error::custom(format!("error parsing '{}': {}", raw, e))),
+            None => Ok(None),
+        }
+    }
+
+    // Some lists are `null` instead of empty, depending on the server version.
+    pub(crate) fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Default + Deserialize<'de>,
+    {
+        Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+    }
+
+    // The histogram object as returned by the API, which is paired with
+    // a timestamp in order to build a [super::HistogramSample].
+    #[derive(Deserialize)]
+    pub(crate) struct Histogram {
+        #[serde(deserialize_with = "deserialize_f64")]
+        pub(crate) count: f64,
+        #[serde(deserialize_with = "deserialize_f64")]
+        pub(crate) sum: f64,
+        #[serde(default)]
+        pub(crate) buckets: Vec<super::HistogramBucket>,
+    }
+}
+
+pub(crate) mod ser {
+    use serde::Serializer;
+    use time::format_description::well_known::Rfc3339;
+    use time::OffsetDateTime;
+    use url::Url;
+
+    // Format a float the way Prometheus does, e.g. `1`, `0.5`, `+Inf` or `1e+21`. Just like
+    // Go's `strconv.FormatFloat`, the exponent notation is used for absolute values of at
+    // least 1e21 or below 1e-6 and the exponent has a sign and at least two digits.
+    pub(crate) fn format_f64(value: f64) -> String {
+        if value.is_nan() {
+            String::from("NaN")
+        } else if value.is_infinite() {
+            String::from(if value > 0.0 { "+Inf" } else { "-Inf" })
+        } else if value != 0.0 && (value.abs() >= 1e21 || value.abs() < 1e-6) {
+            let formatted = format!("{:e}", value);
+            let (mantissa, exponent) = formatted.split_once('e').unwrap();
+            match exponent.strip_prefix('-') {
+                Some(exponent) => format!("{}e-{:0>2}", mantissa, exponent),
+                None => format!("{}e+{:0>2}", mantissa, exponent),
+            }
+        } else {
+            value.to_string()
+        }
+    }
+
+    pub(crate) fn serialize_f64<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format_f64(*value))
+    }
+
+    // Write whole numbers as integers just like Prometheus does, e.g. `1435781451` rather
+    // than `1435781451.0` for timestamps or `60` for durations.
+    pub(crate) fn serialize_timestamp<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+            serializer.serialize_i64(*value as i64)
+        } else {
+            serializer.serialize_f64(*value)
+        }
+    }
+
+    pub(crate) fn serialize_timestamp_opt<S>(
+        value: &Option<f64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(value) => serialize_timestamp(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    // Serializes a timestamp within a tuple, e.g. of a sample.
+    pub(crate) struct Timestamp(pub(crate) f64);
+
+    impl serde::Serialize for Timestamp {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serialize_timestamp(&self.0, serializer)
+        }
+    }
+
+    pub(crate) fn serialize_url<S>(url: &Url, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(url.as_str())
+    }
+
+    pub(crate) fn serialize_rfc3339<S>(
+        datetime: &OffsetDateTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let raw = datetime
+            .format(&Rfc3339)
+            .map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&raw)
+    }
+
+    pub(crate) fn serialize_rfc3339_opt<S>(
+        datetime: &Option<OffsetDateTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match datetime {
+            Some(datetime) => serialize_rfc3339(datetime, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// The status of a response of the Prometheus API.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ResponseStatus {
+    #[serde(rename = "success")]
+    Success,
+    #[serde(rename = "error")]
+    Error,
+}
+
+impl fmt::Display for ResponseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseStatus::Success => write!(f, "success"),
+            ResponseStatus::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The envelope of all responses of the Prometheus API, e.g. to build services that answer
+/// in the same format as Prometheus.
+///
+/// ```rust
+/// use prometheus_http_query::response::{ApiResponse, InstantVector, QueryResultType, Sample};
+/// use std::collections::HashMap;
+///
+/// let metric = HashMap::from([("__name__".to_string(), "up".to_string())]);
+/// let vector = InstantVector::new(metric, Sample::new(1435781451.781, 1.0));
+///
+/// let response = ApiResponse::success(QueryResultType::Vector(vec![vector]));
+///
+/// assert_eq!(
+///     serde_json::to_string(&response).unwrap(),
+///     r#"{"status":"success","data":{"resultType":"vector","result":[{"metric":{"__name__":"up"},"value":[1435781451.781,"1"]}]}}"#
+/// );
+///
+/// let response: ApiResponse<()> = ApiResponse::error("bad_data", "invalid parameter \"query\"");
+///
+/// assert_eq!(
+///     serde_json::to_string(&response).unwrap(),
+///     r#"{"status":"error","errorType":"bad_data","error":"invalid parameter \"query\""}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    pub(crate) status: ResponseStatus,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub(crate) data: Option<T>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) warnings: Vec<String>,
+    #[serde(rename = "errorType")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) error_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    /// Create a successful response.
+    pub fn success(data: T) -> Self {
+        ApiResponse {
+            status: ResponseStatus::Success,
+            data: Some(data),
+            warnings: vec![],
+            error_type: None,
+            error: None,
+        }
+    }
+
+    /// Create an error response, e.g. of type `bad_data`, `execution` or `timeout`.
+    pub fn error(error_type: &str, error: &str) -> Self {
+        ApiResponse {
+            status: ResponseStatus::Error,
+            data: None,
+            warnings: vec![],
+            error_type: Some(error_type.to_string()),
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// Add a warning to this response.
+    pub fn with_warning(mut self, warning: &str) -> Self {
+        self.warnings.push(warning.to_string());
+        self
+    }
+
+    /// Get the status of this response.
+    pub fn status(&self) -> ResponseStatus {
+        self.status
+    }
+
+    /// Get the data of this response, if it was successful.
+    pub fn data(&self) -> Option<&T> {
+        self.data.as_ref()
+    }
+
+    /// Get the warnings of this response.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Get the type of the error, if the response was not successful.
+    pub fn error_type(&self) -> Option<&str> {
+        self.error_type.as_deref()
+    }
+
+    /// Get the error message, if the response was not successful.
+    pub fn error_message(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+// The labels of time series without labels, e.g. of scalars.
+fn no_labels() -> &'static HashMap<String, String> {
+    static EMPTY: OnceLock<HashMap<String, String>> = OnceLock::new();
+    EMPTY.get_or_init(HashMap::new)
+}
+
+// Convert a timestamp in seconds as used by the API into milliseconds.
+pub(crate) fn millis(timestamp: f64) -> i64 {
+    (timestamp * 1000.0).round() as i64
+}
+
+// Name a time series just like Prometheus displays it, e.g. `up{job="prometheus"}`.
+pub(crate) fn series_name(metric: &HashMap<String, String>) -> String {
+    let mut name = metric.get("__name__").cloned().unwrap_or_default();
+
+    let labels: BTreeMap<String, String> = metric
+        .iter()
+        .filter(|(k, _)| *k != "__name__")
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    if labels.is_empty() && name.is_empty() {
+        return String::from("{}");
+    }
+
+    exposition::encode_labels(&mut name, &labels);
+    name
+}
+
+// The labels and float samples of a set of time series.
+pub(crate) type FloatSeries<'a> = Vec<(&'a HashMap<String, String>, &'a [Sample])>;
+
+/// A wrapper for possible result types of expression queries ([crate::Client::query] and [crate::Client::query_range]).
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "resultType", content = "result", rename_all = "lowercase")]
+pub enum QueryResultType {
+    Vector(Vec<InstantVector>),
+    Matrix(Vec<RangeVector>),
+    Scalar(Sample),
+    String(StringSample),
+}
+
+impl QueryResultType {
+    /// If the result type of the query is `vector`, returns an array of [InstantVector]s. Returns `None` otherwise.
+    pub fn as_instant(&self) -> Option<&[InstantVector]> {
+        match self {
+            QueryResultType::Vector(v) => Some(v.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// If the result type of the query is `matrix` returns an array of [RangeVector]s. Returns `None` otherwise.
+    pub fn as_range(&self) -> Option<&[RangeVector]> {
+        match self {
+            QueryResultType::Matrix(v) => Some(v.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// If the result type of the query is `scalar`, returns a single [Sample]. Returns `None` otherwise.
+    pub fn as_scalar(&self) -> Option<&Sample> {
+        match self {
+            QueryResultType::Scalar(v) => Some(&v),
+            _ => None,
+        }
+    }
+
+    /// If the result type of the query is `string`, returns a single [StringSample]. Returns `None` otherwise.
+    pub fn as_string(&self) -> Option<&StringSample> {
+        match self {
+            QueryResultType::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    // The labels and float samples of all time series of a vector, matrix or scalar result,
+    // where a scalar is represented as a time series without labels. Vectors only contain
+    // the series that have a float sample. Returns `None` for results of type `string`.
+    pub(crate) fn float_series(&self) -> Option<FloatSeries<'_>> {
+        match self {
+            QueryResultType::Vector(vector) => Some(
+                vector
+                    .iter()
+                    .filter_map(|v| Some((&v.metric, std::slice::from_ref(v.sample.as_ref()?))))
+                    .collect(),
+            ),
+            QueryResultType::Matrix(matrix) => {
+                Some(matrix.iter().map(|m| (&m.metric, m.samples())).collect())
+            }
+            QueryResultType::Scalar(scalar) => {
+                Some(vec![(no_labels(), std::slice::from_ref(scalar))])
+            }
+            QueryResultType::String(_) => None,
+        }
+    }
+}
+
+/// A single time series containing a single data point/sample.
+///
+/// The sample is either a float sample or a native histogram sample,
+/// so exactly one of [InstantVector::sample] and [InstantVector::histogram] returns `Some`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct InstantVector {
+    pub(crate) metric: HashMap<String, String>,
+    #[serde(rename = "value")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) sample: Option<Sample>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) histogram: Option<HistogramSample>,
+}
+
+impl InstantVector {
+    /// Create a time series with a float sample.
+    pub fn new(metric: HashMap<String, String>, sample: Sample) -> Self {
+        InstantVector {
+            metric,
+            sample: Some(sample),
+            histogram: None,
+        }
+    }
+
+    /// Create a time series with a native histogram sample.
+    pub fn from_histogram(metric: HashMap<String, String>, histogram: HistogramSample) -> Self {
+        InstantVector {
+            metric,
+            sample: None,
+            histogram: Some(histogram),
+        }
+    }
+
+    /// Returns a reference to the set of labels (+ metric name)
+    /// of this time series.
+    pub fn metric(&self) -> &HashMap<String, String> {
+        &self.metric
+    }
+
+    /// Returns a reference to the float sample of this time series, if any.
+    pub fn sample(&self) -> Option<&Sample> {
+        self.sample.as_ref()
+    }
+
+    /// Returns a reference to the native histogram sample of this time series, if any.
+    pub fn histogram(&self) -> Option<&HistogramSample> {
+        self.histogram.as_ref()
+    }
+}
+
+/// A single time series containing a range of data points/samples.
+///
+/// A time series may contain float samples, native histogram samples or both.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RangeVector {
+    pub(crate) metric: HashMap<String, String>,
+    #[serde(rename = "values")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) samples: Vec<Sample>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) histograms: Vec<HistogramSample>,
+}
+
+impl RangeVector {
+    /// Create a time series with float samples.
+    pub fn new(metric: HashMap<String, String>, samples: Vec<Sample>) -> Self {
+        RangeVector {
+            metric,
+            samples,
+            histograms: vec![],
+        }
+    }
+
+    /// Add native histogram samples to this time series.
+    pub fn with_histograms(mut self, histograms: Vec<HistogramSample>) -> Self {
+        self.histograms = histograms;
+        self
+    }
+
+    /// Returns a reference to the set of labels (+ metric name)
+    /// of this time series.
+    pub fn metric(&self) -> &HashMap<String, String> {
+        &self.metric
+    }
+
+    /// Returns a reference to the set of float samples of this time series.
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    /// Returns a reference to the set of native histogram samples of this time series.
+    pub fn histograms(&self) -> &[HistogramSample] {
+        &self.histograms
+    }
+}
+
+/// A single data point.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Sample {
+    pub(crate) timestamp: f64,
+    #[serde(deserialize_with = "de::deserialize_f64")]
+    pub(crate) value: f64,
+}
+
+// Samples are encoded as `[<timestamp>, "<value>"]`.
+impl Serialize for Sample {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&ser::Timestamp(self.timestamp))?;
+        tuple.serialize_element(&ser::format_f64(self.value))?;
+        tuple.end()
+    }
+}
+
+impl Sample {
+    /// Create a sample from a timestamp in seconds and a value.
+    pub fn new(timestamp: f64, value: f64) -> Self {
+        Sample { timestamp, value }
+    }
+
+    /// Returns the timestamp contained in this sample.
+    pub fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+
+    /// Returns the timestamp contained in this sample in milliseconds.
+    pub fn timestamp_millis(&self) -> i64 {
+        millis(self.timestamp)
+    }
+
+    /// Returns the value contained in this sample.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+/// A single data point that contains a native histogram instead of a float value.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(from = "(f64, de::Histogram)")]
+pub struct HistogramSample {
+    pub(crate) timestamp: f64,
+    pub(crate) count: f64,
+    pub(crate) sum: f64,
+    pub(crate) buckets: Vec<HistogramBucket>,
+}
+
+impl From<(f64, de::Histogram)> for HistogramSample {
+    fn from((timestamp, histogram): (f64, de::Histogram)) -> Self {
+        HistogramSample {
+            timestamp,
+            count: histogram.count,
+            sum: histogram.sum,
+            buckets: histogram.buckets,
+        }
+    }
+}
+
+// Histogram samples are encoded as `[<timestamp>, {"count": "<count>", "sum": "<sum>", "buckets": [...]}]`.
+impl Serialize for HistogramSample {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Histogram<'a>(&'a HistogramSample);
+
+        impl Serialize for Histogram<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let len = if self.0.buckets.is_empty() { 2 } else { 3 };
+                let mut object = serializer.serialize_struct("Histogram", len)?;
+                object.serialize_field("count", &ser::format_f64(self.0.count))?;
+                object.serialize_field("sum", &ser::format_f64(self.0.sum))?;
+                if !self.0.buckets.is_empty() {
+                    object.serialize_field("buckets", &self.0.buckets)?;
+                }
+                object.end()
+            }
+        }
+
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&ser::Timestamp(self.timestamp))?;
+        tuple.serialize_element(&Histogram(self))?;
+        tuple.end()
+    }
+}
+
+impl HistogramSample {
+    /// Create a native histogram sample from a timestamp in seconds, the count and sum of
+    /// observations and the populated buckets.
+    pub fn new(timestamp: f64, count: f64, sum: f64, buckets: Vec<HistogramBucket>) -> Self {
+        HistogramSample {
+            timestamp,
+            count,
+            sum,
+            buckets,
+        }
+    }
+
+    /// Returns the timestamp contained in this sample.
+    pub fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+
+    /// Returns the total count of observations of this histogram.
+    pub fn count(&self) -> f64 {
+        self.count
+    }
+
+    /// Returns the sum of all observations of this histogram.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Returns the populated buckets of this histogram, ordered by their boundaries.
+    /// Buckets without any observations are not returned by the API.
+    pub fn buckets(&self) -> &[HistogramBucket] {
+        &self.buckets
+    }
+}
+
+impl<'a> IntoIterator for &'a HistogramSample {
+    type Item = &'a HistogramBucket;
+    type IntoIter = std::slice::Iter<'a, HistogramBucket>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.buckets.iter()
+    }
+}
+
+/// A single bucket of a native histogram.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct HistogramBucket {
+    pub(crate) boundary_rule: BoundaryRule,
+    #[serde(deserialize_with = "de::deserialize_f64")]
+    pub(crate) lower: f64,
+    #[serde(deserialize_with = "de::deserialize_f64")]
+    pub(crate) upper: f64,
+    #[serde(deserialize_with = "de::deserialize_f64")]
+    pub(crate) count: f64,
+}
+
+// Buckets are encoded as `[<boundary rule>, "<lower>", "<upper>", "<count>"]`.
+impl Serialize for HistogramBucket {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(4)?;
+        tuple.serialize_element(&self.boundary_rule)?;
+        tuple.serialize_element(&ser::format_f64(self.lower))?;
+        tuple.serialize_element(&ser::format_f64(self.upper))?;
+        tuple.serialize_element(&ser::format_f64(self.count))?;
+        tuple.end()
+    }
+}
+
+impl HistogramBucket {
+    /// Create a bucket of a native histogram.
+    pub fn new(boundary_rule: BoundaryRule, lower: f64, upper: f64, count: f64) -> Self {
+        HistogramBucket {
+            boundary_rule,
+            lower,
+            upper,
+            count,
+        }
+    }
+
+    /// Returns the rule that determines whether the boundaries of this bucket are inclusive.
+    pub fn boundary_rule(&self) -> BoundaryRule {
+        self.boundary_rule
+    }
+
+    /// Returns the lower boundary of this bucket.
+    pub fn lower(&self) -> f64 {
+        self.lower
+    }
+
+    /// Returns the upper boundary of this bucket.
+    pub fn upper(&self) -> f64 {
+        self.upper
+    }
+
+    /// Returns the count of observations in this bucket.
+    pub fn count(&self) -> f64 {
+        self.count
+    }
+
+    /// Check if a value falls into this bucket with respect to its boundary rule.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::response::HistogramSample;
+    ///
+    /// let raw = r#"[1435781451.781, {"count": "3", "sum": "6", "buckets": [[0, "1", "2", "3"]]}]"#;
+    /// let sample: HistogramSample = serde_json::from_str(raw).unwrap();
+    /// let bucket = &sample.buckets()[0];
+    ///
+    /// assert!(!bucket.contains(1.0));
+    /// assert!(bucket.contains(1.5));
+    /// assert!(bucket.contains(2.0));
+    /// ```
+    pub fn contains(&self, value: f64) -> bool {
+        let above_lower = match self.boundary_rule {
+            BoundaryRule::OpenLeft | BoundaryRule::OpenBoth => value > self.lower,
+            BoundaryRule::OpenRight | BoundaryRule::ClosedBoth => value >= self.lower,
+        };
+
+        let below_upper = match self.boundary_rule {
+            BoundaryRule::OpenRight | BoundaryRule::OpenBoth => value < self.upper,
+            BoundaryRule::OpenLeft | BoundaryRule::ClosedBoth => value <= self.upper,
+        };
+
+        above_lower && below_upper
+    }
+}
+
+/// Possible boundary rules of native histogram buckets, i.e. whether the lower
+/// and/or upper boundary of a bucket is inclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum BoundaryRule {
+    /// The lower boundary is exclusive, the upper boundary is inclusive.
+    OpenLeft,
+    /// The lower boundary is inclusive, the upper boundary is exclusive.
+    OpenRight,
+    /// Both boundaries are exclusive.
+    OpenBoth,
+    /// Both boundaries are inclusive.
+    ClosedBoth,
+}
+
+impl std::convert::TryFrom<u8> for BoundaryRule {
+    type Error = String;
+
+    fn try_from(rule: u8) -> Result<Self, Self::Error> {
+        match rule {
+            0 => Ok(BoundaryRule::OpenLeft),
+            1 => Ok(BoundaryRule::OpenRight),
+            2 => Ok(BoundaryRule::OpenBoth),
+            3 => Ok(BoundaryRule::ClosedBoth),
+            _ => Err(format!("unknown histogram bucket boundary rule '{}'", rule)),
+        }
+    }
+}
+
+impl From<BoundaryRule> for u8 {
+    fn from(rule: BoundaryRule) -> Self {
+        match rule {
+            BoundaryRule::OpenLeft => 0,
+            BoundaryRule::OpenRight => 1,
+            BoundaryRule::OpenBoth => 2,
+            BoundaryRule::ClosedBoth => 3,
+        }
+    }
+}
+
+impl fmt::Display for BoundaryRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoundaryRule::OpenLeft => write!(f, "(lower, upper]"),
+            BoundaryRule::OpenRight => write!(f, "[lower, upper)"),
+            BoundaryRule::OpenBoth => write!(f, "(lower, upper)"),
+            BoundaryRule::ClosedBoth => write!(f, "[lower, upper]"),
+        }
+    }
+}
+
+/// A single data point that contains a string instead of a float value.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct StringSample {
+    pub(crate) timestamp: f64,
+    pub(crate) value: String,
+}
+
+// String samples are encoded as `[<timestamp>, "<value>"]`.
+impl Serialize for StringSample {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&ser::Timestamp(self.timestamp))?;
+        tuple.serialize_element(&self.value)?;
+        tuple.end()
+    }
+}
+
+impl StringSample {
+    /// Create a string sample from a timestamp in seconds and a string.
+    pub fn new(timestamp: f64, value: &str) -> Self {
+        StringSample {
+            timestamp,
+            value: value.to_string(),
+        }
+    }
+
+    /// Returns the timestamp contained in this sample.
+    pub fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+
+    /// Returns the string contained in this sample.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Statistics of a query evaluation as returned by the API when
+/// queries are executed with statistics enabled, e.g. [crate::Client::query_with_stats].
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueryStats {
+    pub(crate) timings: QueryTimings,
+    pub(crate) samples: QuerySamples,
+}
+
+impl QueryStats {
+    /// Get the timing breakdown of the query evaluation.
+    pub fn timings(&self) -> &QueryTimings {
+        &self.timings
+    }
+
+    /// Get the sample counts of the query evaluation.
+    pub fn samples(&self) -> &QuerySamples {
+        &self.samples
+    }
+}
+
+/// Timings of the different stages of a query evaluation in seconds.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueryTimings {
+    #[serde(rename = "evalTotalTime")]
+    pub(crate) eval_total_time: f64,
+    #[serde(rename = "resultSortTime")]
+    #[serde(default)]
+    pub(crate) result_sort_time: f64,
+    #[serde(rename = "queryPreparationTime")]
+    pub(crate) query_preparation_time: f64,
+    #[serde(rename = "innerEvalTime")]
+    pub(crate) inner_eval_time: f64,
+    #[serde(rename = "execQueueTime")]
+    pub(crate) exec_queue_time: f64,
+    #[serde(rename = "execTotalTime")]
+    pub(crate) exec_total_time: f64,
+}
+
+impl QueryTimings {
+    /// Get the total time spent evaluating the query.
+    pub fn eval_total_time(&self) -> f64 {
+        self.eval_total_time
+    }
+
+    /// Get the time spent sorting the query result.
+    pub fn result_sort_time(&self) -> f64 {
+        self.result_sort_time
+    }
+
+    /// Get the time spent preparing the query, e.g. selecting series from storage.
+    pub fn query_preparation_time(&self) -> f64 {
+        self.query_preparation_time
+    }
+
+    /// Get the time spent in the inner evaluation of the query.
+    pub fn inner_eval_time(&self) -> f64 {
+        self.inner_eval_time
+    }
+
+    /// Get the time the query spent waiting in the execution queue.
+    pub fn exec_queue_time(&self) -> f64 {
+        self.exec_queue_time
+    }
+
+    /// Get the total time spent executing the query, including queueing.
+    pub fn exec_total_time(&self) -> f64 {
+        self.exec_total_time
+    }
+}
+
+/// Sample counts of a query evaluation.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuerySamples {
+    #[serde(rename = "totalQueryableSamplesPerStep")]
+    #[serde(default)]
+    pub(crate) total_queryable_samples_per_step: Vec<StepSamples>,
+    #[serde(rename = "totalQueryableSamples")]
+    pub(crate) total_queryable_samples: u64,
+    #[serde(rename = "peakSamples")]
+    pub(crate) peak_samples: u64,
+}
+
+impl QuerySamples {
+    /// Get the number of samples that were loaded from storage at each evaluation step.
+    pub fn total_queryable_samples_per_step(&self) -> &[StepSamples] {
+        &self.total_queryable_samples_per_step
+    }
+
+    /// Get the total number of samples that were loaded from storage.
+    pub fn total_queryable_samples(&self) -> u64 {
+        self.total_queryable_samples
+    }
+
+    /// Get the maximum number of samples that were held in memory at once.
+    pub fn peak_samples(&self) -> u64 {
+        self.peak_samples
+    }
+}
+
+/// The number of samples loaded from storage at a single evaluation step.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct StepSamples {
+    pub(crate) timestamp: f64,
+    pub(crate) samples: u64,
+}
+
+// Step samples are encoded as `[<timestamp>, <samples>]`.
+impl Serialize for StepSamples {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&ser::Timestamp(self.timestamp))?;
+        tuple.serialize_element(&self.samples)?;
+        tuple.end()
+    }
+}
+
+impl StepSamples {
+    /// Returns the timestamp of this evaluation step.
+    pub fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+
+    /// Returns the number of samples loaded at this evaluation step.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+}
+
+/// The result of a request that accepts a `limit`, along with the warnings returned by
+/// the server. It dereferences to the result itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limited<T> {
+    pub(crate) data: T,
+    pub(crate) warnings: Vec<String>,
+}
+
+impl<T> Limited<T> {
+    /// Get a reference to the result.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Consume this wrapper and return the result.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Get the warnings returned by the server.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Check whether the server truncated the result because it exceeded the limit.
+    pub fn is_truncated(&self) -> bool {
+        self.warnings.iter().any(|w| w.contains("truncated"))
+    }
+}
+
+impl<T> std::ops::Deref for Limited<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+/// The currently loaded configuration file of the Prometheus server.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    pub(crate) yaml: String,
+}
+
+impl Config {
+    /// Get the configuration file as YAML string (dumped by Prometheus, not necessarily identical to the file on disk).
+    pub fn yaml(&self) -> &str {
+        &self.yaml
+    }
+
+    /// Parse the configuration file into a typed [PrometheusConfig].
+    pub fn parse(&self) -> Result<PrometheusConfig, Error> {
+        PrometheusConfig::from_yaml(&self.yaml)
+    }
+}
+
+/// Runtime information about the Prometheus server.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeInformation {
+    #[serde(rename = "startTime")]
+    #[serde(deserialize_with = "de::deserialize_rfc3339")]
+    #[serde(serialize_with = "ser::serialize_rfc3339")]
+    pub(crate) start_time: OffsetDateTime,
+    #[serde(rename = "CWD")]
+    pub(crate) cwd: String,
+    #[serde(rename = "reloadConfigSuccess")]
+    pub(crate) reload_config_success: bool,
+    #[serde(rename = "lastConfigTime")]
+    #[serde(deserialize_with = "de::deserialize_rfc3339")]
+    #[serde(serialize_with = "ser::serialize_rfc3339")]
+    pub(crate) last_config_time: OffsetDateTime,
+    #[serde(rename = "corruptionCount")]
+    pub(crate) corruption_count: u64,
+    #[serde(rename = "goroutineCount")]
+    pub(crate) goroutine_count: u64,
+    #[serde(rename = "GOMAXPROCS")]
+    pub(crate) go_max_procs: u64,
+    #[serde(rename = "GOGC")]
+    pub(crate) go_gc: String,
+    #[serde(rename = "GODEBUG")]
+    pub(crate) go_debug: String,
+    #[serde(rename = "storageRetention")]
+    pub(crate) storage_retention: String,
+}
+
+impl RuntimeInformation {
+    /// Get the time when the server was started.
+    pub fn start_time(&self) -> &OffsetDateTime {
+        &self.start_time
+    }
+
+    /// Get the current working directory of the server.
+    pub fn cwd(&self) -> &str {
+        &self.cwd
+    }
+
+    /// Check if the last configuration reload was successful.
+    pub fn reload_config_success(&self) -> bool {
+        self.reload_config_success
+    }
+
+    /// Get the time when the configuration was last (successfully) loaded.
+    pub fn last_config_time(&self) -> &OffsetDateTime {
+        &self.last_config_time
+    }
+
+    /// Get the number of corruptions found in the write-ahead log.
+    pub fn corruption_count(&self) -> u64 {
+        self.corruption_count
+    }
+
+    /// Get the number of goroutines currently running.
+    pub fn goroutine_count(&self) -> u64 {
+        self.goroutine_count
+    }
+
+    /// Get the value of the `GOMAXPROCS` setting of the Go runtime.
+    pub fn go_max_procs(&self) -> u64 {
+        self.go_max_procs
+    }
+
+    /// Get the value of the `GOGC` environment variable.
+    pub fn go_gc(&self) -> &str {
+        &self.go_gc
+    }
+
+    /// Get the value of the `GODEBUG` environment variable.
+    pub fn go_debug(&self) -> &str {
+        &self.go_debug
+    }
+
+    /// Get the configured retention of the storage, e.g. `15d`.
+    pub fn storage_retention(&self) -> &str {
+        &self.storage_retention
+    }
+}
+
+/// Build information about the Prometheus server.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BuildInformation {
+    pub(crate) version: String,
+    pub(crate) revision: String,
+    pub(crate) branch: String,
+    #[serde(rename = "buildUser")]
+    pub(crate) build_user: String,
+    #[serde(rename = "buildDate")]
+    pub(crate) build_date: String,
+    #[serde(rename = "goVersion")]
+    pub(crate) go_version: String,
+}
+
+impl BuildInformation {
+    /// Get the version of the server, e.g. `2.31.1`.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Get the git revision the server was built from.
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    /// Get the git branch the server was built from.
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Get the user who built the server.
+    pub fn build_user(&self) -> &str {
+        &self.build_user
+    }
+
+    /// Get the date when the server was built.
+    pub fn build_date(&self) -> &str {
+        &self.build_date
+    }
+
+    /// Get the version of Go that was used to build the server.
+    pub fn go_version(&self) -> &str {
+        &self.go_version
+    }
 }
 
-/// A wrapper for possible result types of expression queries ([crate::Client::query] and [crate::Client::query_range]).
-#[derive(Debug)]
-pub enum QueryResultType {
-    Vector(Vec<InstantVector>),
-    Matrix(Vec<RangeVector>),
-    Scalar(Sample),
+/// Cardinality statistics about the TSDB of the Prometheus server.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TsdbStatistics {
+    #[serde(rename = "headStats")]
+    pub(crate) head_stats: HeadStatistics,
+    #[serde(rename = "seriesCountByMetricName")]
+    pub(crate) series_count_by_metric_name: Vec<TsdbItemCount>,
+    #[serde(rename = "labelValueCountByLabelName")]
+    pub(crate) label_value_count_by_label_name: Vec<TsdbItemCount>,
+    #[serde(rename = "memoryInBytesByLabelName")]
+    pub(crate) memory_in_bytes_by_label_name: Vec<TsdbItemCount>,
+    #[serde(rename = "seriesCountByLabelValuePair")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) series_count_by_label_value_pair: Option<Vec<TsdbItemCount>>,
 }
 
-impl QueryResultType {
-    /// If the result type of the query is `vector`, returns an array of [InstantVector]s. Returns `None` otherwise.
-    pub fn as_instant(&self) -> Option<&[InstantVector]> {
-        match self {
-            QueryResultType::Vector(v) => Some(v.as_ref()),
-            _ => None,
-        }
+impl TsdbStatistics {
+    /// Get statistics about the head block of the TSDB.
+    pub fn head_stats(&self) -> &HeadStatistics {
+        &self.head_stats
     }
 
-    /// If the result type of the query is `matrix` returns an array of [RangeVector]s. Returns `None` otherwise.
-    pub fn as_range(&self) -> Option<&[RangeVector]> {
-        match self {
-            QueryResultType::Matrix(v) => Some(v.as_ref()),
-            _ => None,
-        }
+    /// Get metric names and the number of their series, ordered by series count.
+    pub fn series_count_by_metric_name(&self) -> &[TsdbItemCount] {
+        &self.series_count_by_metric_name
     }
 
-    /// If the result type of the query is `scalar`, returns a single [Sample]. Returns `None` otherwise.
-    pub fn as_scalar(&self) -> Option<&Sample> {
-        match self {
-            QueryResultType::Scalar(v) => Some(&v),
-            _ => None,
-        }
+    /// Get label names and the number of their distinct values, ordered by value count.
+    pub fn label_value_count_by_label_name(&self) -> &[TsdbItemCount] {
+        &self.label_value_count_by_label_name
+    }
+
+    /// Get label names and the memory used by their values in bytes, ordered by memory usage.
+    pub fn memory_in_bytes_by_label_name(&self) -> &[TsdbItemCount] {
+        &self.memory_in_bytes_by_label_name
+    }
+
+    /// Get label value pairs and the number of their series, ordered by series count.
+    /// This is `None` if the server does not report it.
+    pub fn series_count_by_label_value_pair(&self) -> Option<&[TsdbItemCount]> {
+        self.series_count_by_label_value_pair.as_deref()
     }
 }
 
-/// A single time series containing a single data point/sample.
-#[derive(Debug, PartialEq, Deserialize)]
-pub struct InstantVector {
-    pub(crate) metric: HashMap<String, String>,
-    #[serde(alias = "value")]
-    pub(crate) sample: Sample,
+/// Statistics about the head block of the TSDB.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeadStatistics {
+    #[serde(rename = "numSeries")]
+    pub(crate) num_series: u64,
+    #[serde(rename = "numLabelPairs")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) num_label_pairs: Option<u64>,
+    #[serde(rename = "chunkCount")]
+    pub(crate) chunk_count: u64,
+    #[serde(rename = "minTime")]
+    pub(crate) min_time: i64,
+    #[serde(rename = "maxTime")]
+    pub(crate) max_time: i64,
 }
 
-impl InstantVector {
-    /// Returns a reference to the set of labels (+ metric name)
-    /// of this time series.
-    pub fn metric(&self) -> &HashMap<String, String> {
-        &self.metric
+impl HeadStatistics {
+    /// Get the number of series in the head block.
+    pub fn num_series(&self) -> u64 {
+        self.num_series
+    }
+
+    /// Get the number of label pairs in the head block. This is `None` for older servers
+    /// that do not report it.
+    pub fn num_label_pairs(&self) -> Option<u64> {
+        self.num_label_pairs
+    }
+
+    /// Get the number of chunks in the head block.
+    pub fn chunk_count(&self) -> u64 {
+        self.chunk_count
+    }
+
+    /// Get the lowest timestamp in the head block in milliseconds.
+    pub fn min_time(&self) -> i64 {
+        self.min_time
     }
 
-    /// Returns a reference to the sample of this time series.
-    pub fn sample(&self) -> &Sample {
-        &self.sample
+    /// Get the highest timestamp in the head block in milliseconds.
+    pub fn max_time(&self) -> i64 {
+        self.max_time
     }
 }
 
-/// A single time series containing a range of data points/samples.
-#[derive(Debug, PartialEq, Deserialize)]
-pub struct RangeVector {
-    pub(crate) metric: HashMap<String, String>,
-    #[serde(alias = "values")]
-    pub(crate) samples: Vec<Sample>,
+/// A single item of a TSDB cardinality statistic, e.g. a metric name and its series count.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TsdbItemCount {
+    pub(crate) name: String,
+    pub(crate) value: u64,
 }
 
-impl RangeVector {
-    /// Returns a reference to the set of labels (+ metric name)
-    /// of this time series.
-    pub fn metric(&self) -> &HashMap<String, String> {
-        &self.metric
+impl TsdbItemCount {
+    /// Get the name of this item, e.g. a metric name or label name.
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
-    /// Returns a reference to the set of samples of this time series.
-    pub fn samples(&self) -> &[Sample] {
-        &self.samples
+    /// Get the count of this item, e.g. a series count or memory usage in bytes.
+    pub fn value(&self) -> u64 {
+        self.value
     }
 }
 
-/// A single data point.
-#[derive(Debug, PartialEq, Deserialize)]
-pub struct Sample {
-    pub(crate) timestamp: f64,
+/// Progress of the replay of the write-ahead log.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct WalReplayStatistics {
+    pub(crate) min: u64,
+    pub(crate) max: u64,
+    pub(crate) current: u64,
+}
+
+impl WalReplayStatistics {
+    /// Get the first segment of the write-ahead log to replay.
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    /// Get the last segment of the write-ahead log to replay.
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Get the segment of the write-ahead log that is currently replayed.
+    pub fn current(&self) -> u64 {
+        self.current
+    }
+}
+
+/// A snapshot of the TSDB as created by [crate::Client::snapshot].
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub(crate) name: String,
+}
+
+impl Snapshot {
+    /// Get the name of the snapshot, i.e. the name of the directory below
+    /// `<data-dir>/snapshots` that contains the snapshot.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The outcome of [crate::Client::delete_series].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeriesDeletion {
+    /// The series that would have been deleted, as listed on a dry run.
+    DryRun(Vec<HashMap<String, String>>),
+    /// The series were marked for deletion.
+    Deleted,
+}
+
+/// Label names that commonly hold trace IDs in exemplars, e.g. as set by
+/// the OpenTelemetry, Jaeger or Tempo client libraries.
+const TRACE_ID_LABELS: [&str; 3] = ["trace_id", "traceID", "traceId"];
+
+/// A collection of exemplars that belong to a single time series.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExemplarCollection {
+    #[serde(rename = "seriesLabels")]
+    pub(crate) series_labels: HashMap<String, String>,
+    pub(crate) exemplars: Vec<Exemplar>,
+}
+
+impl ExemplarCollection {
+    /// Create a collection of exemplars of a single time series.
+    pub fn new(series_labels: HashMap<String, String>, exemplars: Vec<Exemplar>) -> Self {
+        ExemplarCollection {
+            series_labels,
+            exemplars,
+        }
+    }
+
+    /// Get the set of labels (+ metric name) of the time series these exemplars belong to.
+    pub fn series_labels(&self) -> &HashMap<String, String> {
+        &self.series_labels
+    }
+
+    /// Get the exemplars of this time series.
+    pub fn exemplars(&self) -> &[Exemplar] {
+        &self.exemplars
+    }
+
+    /// Get the trace IDs of all exemplars of this time series that carry one.
+    /// See [Exemplar::trace_id] for the label names that are considered.
+    pub fn trace_ids(&self) -> impl Iterator<Item = &str> {
+        self.exemplars.iter().filter_map(|e| e.trace_id())
+    }
+}
+
+/// A single exemplar.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Exemplar {
+    pub(crate) labels: HashMap<String, String>,
     #[serde(deserialize_with = "de::deserialize_f64")]
+    #[serde(serialize_with = "ser::serialize_f64")]
     pub(crate) value: f64,
+    #[serde(serialize_with = "ser::serialize_timestamp")]
+    pub(crate) timestamp: f64,
 }
 
-impl Sample {
-    /// Returns the timestamp contained in this sample.
-    pub fn timestamp(&self) -> f64 {
-        self.timestamp
+impl Exemplar {
+    /// Create an exemplar from its labels, value and timestamp in seconds.
+    pub fn new(labels: HashMap<String, String>, value: f64, timestamp: f64) -> Self {
+        Exemplar {
+            labels,
+            value,
+            timestamp,
+        }
     }
 
-    /// Returns the value contained in this sample.
+    /// Get the set of labels of this exemplar, e.g. a trace ID.
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// Get the value of this exemplar.
     pub fn value(&self) -> f64 {
         self.value
     }
+
+    /// Get the timestamp of this exemplar.
+    pub fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+
+    /// Get the trace ID of this exemplar by looking up the commonly used
+    /// label names `trace_id`, `traceID` and `traceId` (in this order).
+    /// Use [Exemplar::trace_id_from] if your instrumentation uses another label name.
+    ///
+    /// ```rust
+    /// use prometheus_http_query::response::Exemplar;
+    ///
+    /// let raw = r#"{"labels": {"traceID": "EpTxMJ40fUus7aGY"}, "value": "6", "timestamp": 1600096945.479}"#;
+    /// let exemplar: Exemplar = serde_json::from_str(raw).unwrap();
+    ///
+    /// assert_eq!(exemplar.trace_id(), Some("EpTxMJ40fUus7aGY"));
+    /// ```
+    pub fn trace_id(&self) -> Option<&str> {
+        TRACE_ID_LABELS
+            .iter()
+            .find_map(|label| self.trace_id_from(label))
+    }
+
+    /// Get the trace ID of this exemplar from a custom label.
+    pub fn trace_id_from(&self, label: &str) -> Option<&str> {
+        self.labels.get(label).map(|id| id.as_str())
+    }
 }
 
 /// Collection of active and dropped targets as returned by the API.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Targets {
-    #[serde(alias = "activeTargets")]
+    #[serde(rename = "activeTargets")]
     pub(crate) active: Vec<ActiveTarget>,
-    #[serde(alias = "droppedTargets")]
+    #[serde(rename = "droppedTargets")]
     pub(crate) dropped: Vec<DroppedTarget>,
+    #[serde(rename = "droppedTargetCounts")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dropped_counts: Option<HashMap<String, usize>>,
 }
 
 impl Targets {
@@ -157,34 +1377,44 @@ impl Targets {
     pub fn dropped(&self) -> &[DroppedTarget] {
         &self.dropped
     }
+
+    /// Get the number of dropped targets per scrape pool (Prometheus >= v2.46).
+    pub fn dropped_counts(&self) -> Option<&HashMap<String, usize>> {
+        self.dropped_counts.as_ref()
+    }
 }
 
 /// A single active target.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ActiveTarget {
-    #[serde(alias = "discoveredLabels")]
+    #[serde(rename = "discoveredLabels")]
     pub(crate) discovered_labels: HashMap<String, String>,
     pub(crate) labels: HashMap<String, String>,
-    #[serde(alias = "scrapePool")]
+    #[serde(rename = "scrapePool")]
     pub(crate) scrape_pool: String,
-    #[serde(alias = "scrapeUrl")]
+    #[serde(rename = "scrapeUrl")]
     #[serde(deserialize_with = "de::deserialize_url")]
+    #[serde(serialize_with = "ser::serialize_url")]
     pub(crate) scrape_url: Url,
-    #[serde(alias = "globalUrl")]
+    #[serde(rename = "globalUrl")]
     #[serde(deserialize_with = "de::deserialize_url")]
+    #[serde(serialize_with = "ser::serialize_url")]
     pub(crate) global_url: Url,
-    #[serde(alias = "lastError")]
+    #[serde(rename = "lastError")]
     pub(crate) last_error: String,
-    #[serde(alias = "lastScrape")]
+    #[serde(rename = "lastScrape")]
     #[serde(deserialize_with = "de::deserialize_rfc3339")]
+    #[serde(serialize_with = "ser::serialize_rfc3339")]
     pub(crate) last_scrape: OffsetDateTime,
-    #[serde(alias = "lastScrapeDuration")]
+    #[serde(rename = "lastScrapeDuration")]
     pub(crate) last_scrape_duration: f64,
     pub(crate) health: TargetHealth,
-    #[serde(alias = "scrapeInterval")]
-    pub(crate) scrape_interval: String,
-    #[serde(alias = "scrapeTimeout")]
-    pub(crate) scrape_timeout: String,
+    #[serde(rename = "scrapeInterval")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scrape_interval: Option<String>,
+    #[serde(rename = "scrapeTimeout")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scrape_timeout: Option<String>,
 }
 
 impl ActiveTarget {
@@ -233,41 +1463,92 @@ impl ActiveTarget {
         self.health
     }
 
-    /// Get the scrape interval of this target.
-    pub fn scrape_interval(&self) -> &str {
-        &self.scrape_interval
+    /// Get the scrape interval of this target (Prometheus >= v2.31).
+    pub fn scrape_interval(&self) -> Option<&str> {
+        self.scrape_interval.as_deref()
     }
 
-    /// Get the scrape timeout of this target.
-    pub fn scrape_timeout(&self) -> &str {
-        &self.scrape_timeout
+    /// Get the scrape timeout of this target (Prometheus >= v2.31).
+    pub fn scrape_timeout(&self) -> Option<&str> {
+        self.scrape_timeout.as_deref()
     }
 }
 
 /// A single dropped target.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DroppedTarget {
-    #[serde(alias = "discoveredLabels")]
+    #[serde(rename = "discoveredLabels")]
     pub(crate) discovered_labels: HashMap<String, String>,
 }
 
 impl DroppedTarget {
+    /// Create a dropped target from its discovered labels.
+    pub fn new(discovered_labels: HashMap<String, String>) -> Self {
+        DroppedTarget { discovered_labels }
+    }
+
     /// Get a set of unmodified labels as before relabelling occurred.
     pub fn discovered_labels(&self) -> &HashMap<String, String> {
         &self.discovered_labels
     }
 }
 
+/// A list of rule groups, possibly only one page of all groups (see [crate::RuleFilter::group_limit]).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuleGroups {
+    pub(crate) groups: Vec<RuleGroup>,
+    #[serde(rename = "groupNextToken")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) next_token: Option<String>,
+}
+
+impl RuleGroups {
+    /// Get a reference to all rule groups of this page.
+    pub fn groups(&self) -> &[RuleGroup] {
+        &self.groups
+    }
+
+    /// Get the token to request the next page of rule groups with, if there is one.
+    pub fn next_token(&self) -> Option<&str> {
+        self.next_token.as_deref()
+    }
+}
+
 /// A group of rules.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RuleGroup {
+    #[serde(deserialize_with = "de::deserialize_null_default")]
     pub(crate) rules: Vec<Rule>,
     pub(crate) file: String,
+    #[serde(serialize_with = "ser::serialize_timestamp")]
     pub(crate) interval: f64,
     pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) limit: Option<u64>,
+    #[serde(rename = "lastEvaluation")]
+    #[serde(default, deserialize_with = "de::deserialize_rfc3339_opt")]
+    #[serde(serialize_with = "ser::serialize_rfc3339_opt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_evaluation: Option<OffsetDateTime>,
+    #[serde(rename = "evaluationTime")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) evaluation_time: Option<f64>,
 }
 
 impl RuleGroup {
+    /// Create a rule group that is evaluated in the given interval in seconds.
+    pub fn new(name: &str, file: &str, interval: f64, rules: Vec<Rule>) -> Self {
+        RuleGroup {
+            rules,
+            file: file.to_string(),
+            interval,
+            name: name.to_string(),
+            limit: None,
+            last_evaluation: None,
+            evaluation_time: None,
+        }
+    }
+
     /// Get a reference to all rules associated with this group.
     pub fn rules(&self) -> &[Rule] {
         &self.rules
@@ -287,31 +1568,114 @@ impl RuleGroup {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Get the maximum number of alerts or series this group may produce, where 0 means
+    /// no limit (Prometheus >= v2.31).
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// Get the time when this group was last evaluated (Prometheus >= v2.27).
+    pub fn last_evaluation(&self) -> Option<&OffsetDateTime> {
+        self.last_evaluation.as_ref()
+    }
+
+    /// Get the duration of the last evaluation in seconds (Prometheus >= v2.27).
+    pub fn evaluation_time(&self) -> Option<f64> {
+        self.evaluation_time
+    }
 }
 
 /// A wrapper for different types of rules that the HTTP API may return.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Rule {
-    #[serde(alias = "recording")]
+    #[serde(rename = "recording")]
     Recording(RecordingRule),
-    #[serde(alias = "alerting")]
+    #[serde(rename = "alerting")]
     Alerting(AlertingRule),
+    /// A rule of a type that is not known to this crate.
+    #[serde(rename = "unknown")]
+    #[serde(other)]
+    Unknown,
 }
 
 /// An alerting rule.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AlertingRule {
+    #[serde(default, deserialize_with = "de::deserialize_null_default")]
     pub(crate) alerts: Vec<Alert>,
+    #[serde(default, deserialize_with = "de::deserialize_null_default")]
     pub(crate) annotations: HashMap<String, String>,
+    #[serde(serialize_with = "ser::serialize_timestamp")]
     pub(crate) duration: f64,
     pub(crate) health: RuleHealth,
+    #[serde(default, deserialize_with = "de::deserialize_null_default")]
     pub(crate) labels: HashMap<String, String>,
     pub(crate) name: String,
     pub(crate) query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) state: Option<AlertState>,
+    #[serde(rename = "keepFiringFor")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "ser::serialize_timestamp_opt")]
+    pub(crate) keep_firing_for: Option<f64>,
+    #[serde(rename = "lastError")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_error: Option<String>,
+    #[serde(rename = "lastEvaluation")]
+    #[serde(default, deserialize_with = "de::deserialize_rfc3339_opt")]
+    #[serde(serialize_with = "ser::serialize_rfc3339_opt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_evaluation: Option<OffsetDateTime>,
+    #[serde(rename = "evaluationTime")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) evaluation_time: Option<f64>,
 }
 
 impl AlertingRule {
+    /// Create an alerting rule without any alerts.
+    pub fn new(name: &str, query: &str, duration: f64, health: RuleHealth) -> Self {
+        AlertingRule {
+            alerts: vec![],
+            annotations: HashMap::new(),
+            duration,
+            health,
+            labels: HashMap::new(),
+            name: name.to_string(),
+            query: query.to_string(),
+            state: None,
+            keep_firing_for: None,
+            last_error: None,
+            last_evaluation: None,
+            evaluation_time: None,
+        }
+    }
+
+    /// Set the alerts fired due to this rule.
+    pub fn with_alerts(mut self, alerts: Vec<Alert>) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Set the labels of this rule.
+    pub fn with_labels(mut self, labels: HashMap<String, String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Set the annotations of this rule.
+    pub fn with_annotations(mut self, annotations: HashMap<String, String>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Set the state of this rule.
+    pub fn with_state(mut self, state: AlertState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
     /// Get a list of active alerts fired due to this alerting rule.
     pub fn alerts(&self) -> &[Alert] {
         &self.alerts
@@ -346,18 +1710,75 @@ impl AlertingRule {
     pub fn query(&self) -> &str {
         &self.query
     }
+
+    /// Get the state of this rule, i.e. of its most severe alert (Prometheus >= v2.27).
+    pub fn state(&self) -> Option<AlertState> {
+        self.state
+    }
+
+    /// Get the duration in seconds that alerts keep firing for after the condition has
+    /// cleared (Prometheus >= v2.42).
+    pub fn keep_firing_for(&self) -> Option<f64> {
+        self.keep_firing_for
+    }
+
+    /// Get the error of the last evaluation, if any.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref().filter(|e| !e.is_empty())
+    }
+
+    /// Get the time when this rule was last evaluated (Prometheus >= v2.27).
+    pub fn last_evaluation(&self) -> Option<&OffsetDateTime> {
+        self.last_evaluation.as_ref()
+    }
+
+    /// Get the duration of the last evaluation in seconds (Prometheus >= v2.27).
+    pub fn evaluation_time(&self) -> Option<f64> {
+        self.evaluation_time
+    }
 }
 
 /// A recording rule.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RecordingRule {
     pub(crate) health: RuleHealth,
     pub(crate) name: String,
     pub(crate) query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) labels: Option<HashMap<String, String>>,
+    #[serde(rename = "lastError")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_error: Option<String>,
+    #[serde(rename = "lastEvaluation")]
+    #[serde(default, deserialize_with = "de::deserialize_rfc3339_opt")]
+    #[serde(serialize_with = "ser::serialize_rfc3339_opt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) last_evaluation: Option<OffsetDateTime>,
+    #[serde(rename = "evaluationTime")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) evaluation_time: Option<f64>,
 }
 
 impl RecordingRule {
+    /// Create a recording rule.
+    pub fn new(name: &str, query: &str, health: RuleHealth) -> Self {
+        RecordingRule {
+            health,
+            name: name.to_string(),
+            query: query.to_string(),
+            labels: None,
+            last_error: None,
+            last_evaluation: None,
+            evaluation_time: None,
+        }
+    }
+
+    /// Set the labels of this rule.
+    pub fn with_labels(mut self, labels: HashMap<String, String>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
     /// Get the health state of this rule.
     pub fn health(&self) -> RuleHealth {
         self.health
@@ -377,21 +1798,60 @@ impl RecordingRule {
     pub fn labels(&self) -> &Option<HashMap<String, String>> {
         &self.labels
     }
+
+    /// Get the error of the last evaluation, if any.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref().filter(|e| !e.is_empty())
+    }
+
+    /// Get the time when this rule was last evaluated (Prometheus >= v2.27).
+    pub fn last_evaluation(&self) -> Option<&OffsetDateTime> {
+        self.last_evaluation.as_ref()
+    }
+
+    /// Get the duration of the last evaluation in seconds (Prometheus >= v2.27).
+    pub fn evaluation_time(&self) -> Option<f64> {
+        self.evaluation_time
+    }
 }
 
 /// A single alert.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Alert {
-    #[serde(alias = "activeAt")]
+    #[serde(rename = "activeAt")]
     #[serde(deserialize_with = "de::deserialize_rfc3339")]
+    #[serde(serialize_with = "ser::serialize_rfc3339")]
     pub(crate) active_at: OffsetDateTime,
     pub(crate) annotations: HashMap<String, String>,
     pub(crate) labels: HashMap<String, String>,
     pub(crate) state: AlertState,
     pub(crate) value: String,
+    #[serde(rename = "keepFiringSince")]
+    #[serde(default, deserialize_with = "de::deserialize_rfc3339_opt")]
+    #[serde(serialize_with = "ser::serialize_rfc3339_opt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) keep_firing_since: Option<OffsetDateTime>,
 }
 
 impl Alert {
+    /// Create an alert.
+    pub fn new(
+        labels: HashMap<String, String>,
+        annotations: HashMap<String, String>,
+        state: AlertState,
+        active_at: OffsetDateTime,
+        value: &str,
+    ) -> Self {
+        Alert {
+            active_at,
+            annotations,
+            labels,
+            state,
+            value: value.to_string(),
+            keep_firing_since: None,
+        }
+    }
+
     /// Get the time when this alert started firing.
     pub fn active_at(&self) -> &OffsetDateTime {
         &self.active_at
@@ -416,6 +1876,12 @@ impl Alert {
     pub fn value(&self) -> &str {
         &self.value
     }
+
+    /// Get the time since when this alert keeps firing although its condition has cleared
+    /// (Prometheus >= v2.42).
+    pub fn keep_firing_since(&self) -> Option<&OffsetDateTime> {
+        self.keep_firing_since.as_ref()
+    }
 }
 
 /// Collection of active and dropped alertmanagers as returned by the API.
@@ -425,7 +1891,31 @@ pub struct Alertmanagers {
     pub(crate) dropped: Vec<Url>,
 }
 
+// Alertmanagers are encoded as `{"activeAlertmanagers": [{"url": "<url>"}], "droppedAlertmanagers": [...]}`.
+impl Serialize for Alertmanagers {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Entry<'a> {
+            url: &'a str,
+        }
+
+        fn entries(urls: &[Url]) -> Vec<Entry<'_>> {
+            urls.iter().map(|u| Entry { url: u.as_str() }).collect()
+        }
+
+        let mut object = serializer.serialize_struct("Alertmanagers", 2)?;
+        object.serialize_field("activeAlertmanagers", &entries(&self.active))?;
+        object.serialize_field("droppedAlertmanagers", &entries(&self.dropped))?;
+        object.end()
+    }
+}
+
 impl Alertmanagers {
+    /// Create a collection of active and dropped alertmanagers.
+    pub fn new(active: Vec<Url>, dropped: Vec<Url>) -> Self {
+        Alertmanagers { active, dropped }
+    }
+
     /// Get a list of currently active alertmanagers.
     pub fn active(&self) -> &[Url] {
         &self.active
@@ -438,23 +1928,23 @@ impl Alertmanagers {
 }
 
 /// Possible metric types that the HTTP API may return.
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MetricType {
-    #[serde(alias = "counter")]
+    #[serde(rename = "counter")]
     Counter,
-    #[serde(alias = "gauge")]
+    #[serde(rename = "gauge")]
     Gauge,
-    #[serde(alias = "histogram")]
+    #[serde(rename = "histogram")]
     Histogram,
-    #[serde(alias = "gaugehistogram")]
+    #[serde(rename = "gaugehistogram")]
     GaugeHistogram,
-    #[serde(alias = "summary")]
+    #[serde(rename = "summary")]
     Summary,
-    #[serde(alias = "info")]
+    #[serde(rename = "info")]
     Info,
-    #[serde(alias = "stateset")]
+    #[serde(rename = "stateset")]
     Stateset,
-    #[serde(alias = "unknown")]
+    #[serde(rename = "unknown")]
     Unknown,
 }
 
@@ -474,11 +1964,12 @@ impl fmt::Display for MetricType {
 }
 
 /// A target metadata object.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TargetMetadata {
     pub(crate) target: HashMap<String, String>,
-    #[serde(alias = "type")]
+    #[serde(rename = "type")]
     pub(crate) metric_type: MetricType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) metric: Option<String>,
     pub(crate) help: String,
     pub(crate) unit: String,
@@ -512,15 +2003,24 @@ impl TargetMetadata {
 }
 
 /// A metric metadata object
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MetricMetadata {
-    #[serde(alias = "type")]
+    #[serde(rename = "type")]
     pub(crate) metric_type: MetricType,
     pub(crate) help: String,
     pub(crate) unit: String,
 }
 
 impl MetricMetadata {
+    /// Create the metadata of a metric.
+    pub fn new(metric_type: MetricType, help: &str, unit: &str) -> Self {
+        MetricMetadata {
+            metric_type,
+            help: help.to_string(),
+            unit: unit.to_string(),
+        }
+    }
+
     /// Get the metric type.
     pub fn metric_type(&self) -> MetricType {
         self.metric_type
@@ -536,3 +2036,547 @@ impl MetricMetadata {
         &self.unit
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_sample_deserialization() {
+        let data = r#"[1623855855.123, "some string"]"#;
+
+        let result: StringSample = serde_json::from_str(data).unwrap();
+
+        let expected = StringSample {
+            timestamp: 1623855855.123,
+            value: String::from("some string"),
+        };
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_histogram_sample_deserialization() {
+        let data = r#"[
+            1435781451.781,
+            {
+                "count": "10",
+                "sum": "21.5",
+                "buckets": [
+                    [0, "-1", "0", "2"],
+                    [3, "0", "0", "1"],
+                    [1, "1", "2", "7"]
+                ]
+            }
+        ]"#;
+
+        let result: HistogramSample = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.timestamp(), 1435781451.781);
+        assert_eq!(result.count(), 10.0);
+        assert_eq!(result.sum(), 21.5);
+
+        let rules: Vec<BoundaryRule> = result.into_iter().map(|b| b.boundary_rule()).collect();
+
+        assert_eq!(
+            rules,
+            vec![
+                BoundaryRule::OpenLeft,
+                BoundaryRule::ClosedBoth,
+                BoundaryRule::OpenRight
+            ]
+        );
+
+        assert!(result.buckets()[1].contains(0.0));
+        assert!(!result.buckets()[2].contains(2.0));
+    }
+
+    #[test]
+    fn test_instant_vector_with_histogram_deserialization() {
+        let data = r#"{
+            "metric": { "__name__": "some_histogram" },
+            "histogram": [1435781451.781, { "count": "0", "sum": "0" }]
+        }"#;
+
+        let result: InstantVector = serde_json::from_str(data).unwrap();
+
+        assert!(result.sample().is_none());
+        assert!(result.histogram().unwrap().buckets().is_empty());
+    }
+
+    #[test]
+    fn test_range_vector_with_mixed_samples_deserialization() {
+        let data = r#"{
+            "metric": { "__name__": "some_metric" },
+            "values": [[1435781451.781, "1"]],
+            "histograms": [[1435781466.781, { "count": "1", "sum": "1", "buckets": [[0, "0", "1", "1"]] }]]
+        }"#;
+
+        let result: RangeVector = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.samples().len(), 1);
+        assert_eq!(result.histograms().len(), 1);
+    }
+
+    #[test]
+    fn test_query_stats_deserialization() {
+        let data = r#"{
+            "timings": {
+                "evalTotalTime": 0.000447452,
+                "resultSortTime": 0,
+                "queryPreparationTime": 0.000112108,
+                "innerEvalTime": 0.000319813,
+                "execQueueTime": 0.000021441,
+                "execTotalTime": 0.000487069
+            },
+            "samples": {
+                "totalQueryableSamplesPerStep": [[1435781451.781, 12], [1435781466.781, 14]],
+                "totalQueryableSamples": 26,
+                "peakSamples": 14
+            }
+        }"#;
+
+        let result: QueryStats = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.timings().exec_queue_time(), 0.000021441);
+        assert_eq!(result.samples().total_queryable_samples(), 26);
+        assert_eq!(result.samples().peak_samples(), 14);
+        assert_eq!(
+            result.samples().total_queryable_samples_per_step()[1],
+            StepSamples {
+                timestamp: 1435781466.781,
+                samples: 14
+            }
+        );
+    }
+
+    #[test]
+    fn test_exemplar_collection_deserialization() {
+        let data = r#"[
+            {
+                "seriesLabels": {
+                    "__name__": "test_exemplar_metric_total",
+                    "instance": "localhost:8090",
+                    "job": "prometheus",
+                    "service": "bar"
+                },
+                "exemplars": [
+                    {
+                        "labels": { "trace_id": "EpTxMJ40fUus7aGY" },
+                        "value": "6",
+                        "timestamp": 1600096945.479
+                    },
+                    {
+                        "labels": { "span_id": "Olp9XHlq763ccsfa" },
+                        "value": "19",
+                        "timestamp": 1600096955.479
+                    }
+                ]
+            }
+        ]"#;
+
+        let result: Vec<ExemplarCollection> = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].exemplars()[1].value(), 19.0);
+        assert_eq!(
+            result[0].trace_ids().collect::<Vec<&str>>(),
+            vec!["EpTxMJ40fUus7aGY"]
+        );
+    }
+
+    #[test]
+    fn test_runtime_information_deserialization() {
+        let data = r#"{
+            "startTime": "2019-11-02T17:23:59.301361365+01:00",
+            "CWD": "/",
+            "reloadConfigSuccess": true,
+            "lastConfigTime": "2019-11-02T17:23:59+01:00",
+            "timeSeriesCount": 873,
+            "corruptionCount": 0,
+            "goroutineCount": 48,
+            "GOMAXPROCS": 4,
+            "GOGC": "",
+            "GODEBUG": "",
+            "storageRetention": "15d"
+        }"#;
+
+        let result: RuntimeInformation = serde_json::from_str(data).unwrap();
+
+        assert!(result.reload_config_success());
+        assert_eq!(result.go_max_procs(), 4);
+        assert_eq!(result.storage_retention(), "15d");
+    }
+
+    #[test]
+    fn test_tsdb_statistics_deserialization() {
+        let data = r#"{
+            "headStats": {
+                "numSeries": 508,
+                "chunkCount": 937,
+                "minTime": 1591516800000,
+                "maxTime": 1598896800143,
+                "numLabelPairs": 300
+            },
+            "seriesCountByMetricName": [
+                { "name": "net_conntrack_dialer_conn_failed_total", "value": 20 },
+                { "name": "prometheus_http_request_duration_seconds_bucket", "value": 20 }
+            ],
+            "labelValueCountByLabelName": [
+                { "name": "__name__", "value": 211 }
+            ],
+            "memoryInBytesByLabelName": [
+                { "name": "__name__", "value": 8266 }
+            ],
+            "seriesCountByLabelValuePair": [
+                { "name": "job=prometheus", "value": 425 }
+            ]
+        }"#;
+
+        let result: TsdbStatistics = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.head_stats().num_series(), 508);
+        assert_eq!(result.series_count_by_metric_name().len(), 2);
+        assert_eq!(result.memory_in_bytes_by_label_name()[0].value(), 8266);
+        assert_eq!(result.head_stats().num_label_pairs(), Some(300));
+        assert_eq!(
+            result.series_count_by_label_value_pair().unwrap()[0].name(),
+            "job=prometheus"
+        );
+
+        // As returned by Prometheus v2.26.
+        let data = r#"{
+            "headStats": {
+                "numSeries": 508,
+                "chunkCount": 937,
+                "minTime": 1591516800000,
+                "maxTime": 1598896800143
+            },
+            "seriesCountByMetricName": [
+                { "name": "net_conntrack_dialer_conn_failed_total", "value": 20 }
+            ],
+            "labelValueCountByLabelName": [
+                { "name": "__name__", "value": 211 }
+            ],
+            "memoryInBytesByLabelName": [
+                { "name": "__name__", "value": 8266 }
+            ],
+            "seriesCountByLabelValuePair": [
+                { "name": "job=prometheus", "value": 425 }
+            ]
+        }"#;
+
+        let result: TsdbStatistics = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.head_stats().num_series(), 508);
+        assert!(result.head_stats().num_label_pairs().is_none());
+        assert_eq!(result.series_count_by_label_value_pair().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rule_group_deserialization() {
+        // As returned by Prometheus v2.26.
+        let data = r#"{
+            "name": "example",
+            "file": "/rules.yaml",
+            "interval": 60,
+            "rules": [
+                {
+                    "name": "job:http_inprogress_requests:sum",
+                    "query": "sum by (job) (http_inprogress_requests)",
+                    "health": "ok",
+                    "type": "recording"
+                }
+            ]
+        }"#;
+
+        let result: RuleGroup = serde_json::from_str(data).unwrap();
+
+        assert!(result.limit().is_none());
+        assert!(result.last_evaluation().is_none());
+        assert!(matches!(&result.rules()[0], Rule::Recording(r) if r.last_error().is_none()));
+
+        // As returned by Prometheus v3.x.
+        let data = r#"{
+            "name": "example",
+            "file": "/rules.yaml",
+            "interval": 60,
+            "limit": 0,
+            "evaluationTime": 0.000312,
+            "lastEvaluation": "2024-11-19T10:17:41.416Z",
+            "rules": [
+                {
+                    "state": "firing",
+                    "name": "HighRequestLatency",
+                    "query": "job:request_latency_seconds:mean5m > 0.5",
+                    "duration": 600,
+                    "keepFiringFor": 300,
+                    "labels": { "severity": "page" },
+                    "annotations": { "summary": "High request latency" },
+                    "alerts": [
+                        {
+                            "labels": { "alertname": "HighRequestLatency", "severity": "page" },
+                            "annotations": { "summary": "High request latency" },
+                            "state": "firing",
+                            "activeAt": "2024-11-19T09:02:11.416Z",
+                            "keepFiringSince": "2024-11-19T10:12:11.416Z",
+                            "value": "1e+00"
+                        }
+                    ],
+                    "health": "ok",
+                    "lastError": "",
+                    "evaluationTime": 0.000221,
+                    "lastEvaluation": "2024-11-19T10:17:41.416Z",
+                    "type": "alerting"
+                },
+                {
+                    "name": "something_new",
+                    "health": "ok",
+                    "type": "future"
+                }
+            ]
+        }"#;
+
+        let result: RuleGroup = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.limit(), Some(0));
+        assert!(result.last_evaluation().is_some());
+
+        match &result.rules()[0] {
+            Rule::Alerting(rule) => {
+                assert_eq!(rule.keep_firing_for(), Some(300.0));
+                assert!(matches!(rule.state(), Some(AlertState::Firing)));
+                assert!(rule.last_error().is_none());
+                assert!(rule.alerts()[0].keep_firing_since().is_some());
+            }
+            _ => panic!("expected an alerting rule"),
+        }
+
+        assert!(matches!(result.rules()[1], Rule::Unknown));
+    }
+
+    #[test]
+    fn test_targets_deserialization() {
+        // As returned by Prometheus v2.26, without scrape interval and timeout.
+        let data = r#"{
+            "activeTargets": [
+                {
+                    "discoveredLabels": { "__address__": "127.0.0.1:9090", "job": "prometheus" },
+                    "labels": { "instance": "127.0.0.1:9090", "job": "prometheus" },
+                    "scrapePool": "prometheus",
+                    "scrapeUrl": "http://127.0.0.1:9090/metrics",
+                    "globalUrl": "http://example-prometheus:9090/metrics",
+                    "lastError": "",
+                    "lastScrape": "2017-01-17T15:07:44.723715405+01:00",
+                    "lastScrapeDuration": 0.050688943,
+                    "health": "up"
+                }
+            ],
+            "droppedTargets": []
+        }"#;
+
+        let result: Targets = serde_json::from_str(data).unwrap();
+
+        assert!(result.active()[0].scrape_interval().is_none());
+        assert!(result.dropped_counts().is_none());
+
+        // As returned by Prometheus v3.x.
+        let data = r#"{
+            "activeTargets": [
+                {
+                    "discoveredLabels": { "__address__": "127.0.0.1:9090", "job": "prometheus" },
+                    "labels": { "instance": "127.0.0.1:9090", "job": "prometheus" },
+                    "scrapePool": "prometheus",
+                    "scrapeUrl": "http://127.0.0.1:9090/metrics",
+                    "globalUrl": "http://example-prometheus:9090/metrics",
+                    "lastError": "",
+                    "lastScrape": "2017-01-17T15:07:44.723715405+01:00",
+                    "lastScrapeDuration": 0.050688943,
+                    "health": "flapping",
+                    "scrapeInterval": "1m",
+                    "scrapeTimeout": "10s"
+                }
+            ],
+            "droppedTargets": [
+                {
+                    "discoveredLabels": { "__address__": "127.0.0.1:9100", "job": "node" }
+                }
+            ],
+            "droppedTargetCounts": { "node": 1 }
+        }"#;
+
+        let result: Targets = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.active()[0].scrape_interval(), Some("1m"));
+        assert!(matches!(result.active()[0].health(), TargetHealth::Unknown));
+        assert_eq!(result.dropped_counts().unwrap().get("node"), Some(&1));
+    }
+
+    #[test]
+    fn test_vector_serialization() {
+        let data = r#"{"resultType":"vector","result":[{"metric":{"__name__":"up","job":"prometheus"},"value":[1435781451.781,"1"]},{"metric":{"__name__":"up","job":"node"},"value":[1435781451.781,"+Inf"]}]}"#;
+
+        let result: QueryResultType = serde_json::from_str(data).unwrap();
+        let value: serde_json::Value = serde_json::to_value(&result).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::from_str::<serde_json::Value>(data).unwrap()
+        );
+        assert_eq!(
+            serde_json::from_value::<QueryResultType>(value).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn test_whole_second_serialization() {
+        let data = r#"{"resultType":"matrix","result":[{"metric":{"job":"node"},"values":[[1435781451,"1"],[1435781466.5,"0"]]}]}"#;
+
+        let result: QueryResultType = serde_json::from_str(data).unwrap();
+
+        assert_eq!(serde_json::to_string(&result).unwrap(), data);
+
+        let exemplar = Exemplar::new(HashMap::new(), 6.0, 1600096945.0);
+
+        assert_eq!(
+            serde_json::to_string(&exemplar).unwrap(),
+            r#"{"labels":{},"value":"6","timestamp":1600096945}"#
+        );
+    }
+
+    #[test]
+    fn test_matrix_serialization() {
+        let metric = HashMap::from([("__name__".to_string(), "up".to_string())]);
+        let matrix = QueryResultType::Matrix(vec![RangeVector::new(
+            metric,
+            vec![
+                Sample::new(1435781430.781, 1.0),
+                Sample::new(1435781445.781, 0.5),
+            ],
+        )]);
+
+        assert_eq!(
+            serde_json::to_string(&matrix).unwrap(),
+            r#"{"resultType":"matrix","result":[{"metric":{"__name__":"up"},"values":[[1435781430.781,"1"],[1435781445.781,"0.5"]]}]}"#
+        );
+
+        let scalar = QueryResultType::Scalar(Sample::new(1435781451.781, f64::NAN));
+
+        assert_eq!(
+            serde_json::to_string(&scalar).unwrap(),
+            r#"{"resultType":"scalar","result":[1435781451.781,"NaN"]}"#
+        );
+    }
+
+    #[test]
+    fn test_extreme_value_serialization() {
+        let vector = QueryResultType::Vector(vec![
+            InstantVector::new(HashMap::new(), Sample::new(1435781451.0, 1e300)),
+            InstantVector::new(HashMap::new(), Sample::new(1435781451.0, -1e-300)),
+            InstantVector::new(HashMap::new(), Sample::new(1435781451.0, 1.5e21)),
+            InstantVector::new(HashMap::new(), Sample::new(1435781451.0, 2.5e-7)),
+            InstantVector::new(HashMap::new(), Sample::new(1435781451.0, 1e20)),
+            InstantVector::new(HashMap::new(), Sample::new(1435781451.0, 1e-6)),
+        ]);
+
+        let raw = serde_json::to_string(&vector).unwrap();
+
+        let values: Vec<&str> = raw
+            .split("\"value\":[1435781451,\"")
+            .skip(1)
+            .map(|v| v.split('"').next().unwrap())
+            .collect();
+
+        assert_eq!(
+            values,
+            [
+                "1e+300",
+                "-1e-300",
+                "1.5e+21",
+                "2.5e-07",
+                "100000000000000000000",
+                "0.000001"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_histogram_serialization() {
+        let data = r#"[1435781451.781,{"count":"10","sum":"3.5","buckets":[[0,"-0.001","0","2"],[3,"1","2","8"]]}]"#;
+
+        let result: HistogramSample = serde_json::from_str(data).unwrap();
+
+        assert_eq!(serde_json::to_string(&result).unwrap(), data);
+
+        let empty = HistogramSample::new(1435781451.781, 0.0, 0.0, vec![]);
+
+        assert_eq!(
+            serde_json::to_string(&empty).unwrap(),
+            r#"[1435781451.781,{"count":"0","sum":"0"}]"#
+        );
+    }
+
+    #[test]
+    fn test_envelope_serialization() {
+        let data = r#"{"status":"success","data":{"resultType":"string","result":[1435781451.781,"foo"]},"warnings":["results truncated"]}"#;
+
+        let result: ApiResponse<QueryResultType> = serde_json::from_str(data).unwrap();
+
+        assert_eq!(result.status(), ResponseStatus::Success);
+        assert_eq!(result.warnings(), ["results truncated"]);
+        assert_eq!(serde_json::to_string(&result).unwrap(), data);
+
+        let data = r#"{"status":"error","errorType":"timeout","error":"query timed out"}"#;
+
+        let result: ApiResponse<QueryResultType> = serde_json::from_str(data).unwrap();
+
+        assert!(result.data().is_none());
+        assert_eq!(result.error_type(), Some("timeout"));
+        assert_eq!(serde_json::to_string(&result).unwrap(), data);
+    }
+
+    #[test]
+    fn test_rule_group_serialization() {
+        let active_at = OffsetDateTime::from_unix_timestamp(1600096945).unwrap();
+        let alert = Alert::new(
+            HashMap::from([("alertname".to_string(), "HighLatency".to_string())]),
+            HashMap::new(),
+            AlertState::Firing,
+            active_at,
+            "1e+00",
+        );
+        let rules = vec![
+            Rule::Alerting(
+                AlertingRule::new("HighLatency", "latency > 1", 600.0, RuleHealth::Good)
+                    .with_alerts(vec![alert]),
+            ),
+            Rule::Recording(RecordingRule::new(
+                "job:up:sum",
+                "sum by (job) (up)",
+                RuleHealth::Good,
+            )),
+        ];
+        let group = RuleGroup::new("example", "/rules.yaml", 60.0, rules);
+
+        let raw = serde_json::to_string(&group).unwrap();
+
+        assert!(raw.contains(r#""interval":60,"#));
+        assert!(raw.contains(r#""duration":600,"#));
+
+        let value = serde_json::to_value(&group).unwrap();
+
+        assert_eq!(value["rules"][0]["type"], "alerting");
+        assert_eq!(
+            value["rules"][0]["alerts"][0]["activeAt"],
+            "2020-09-14T15:22:25Z"
+        );
+        assert_eq!(value["rules"][1]["type"], "recording");
+        assert_eq!(value["rules"][1]["health"], "ok");
+
+        let result: RuleGroup = serde_json::from_value(value).unwrap();
+
+        assert_eq!(result.rules().len(), 2);
+        assert!(matches!(result.rules()[0], Rule::Alerting(_)));
+    }
+}
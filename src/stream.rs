@@ -0,0 +1,437 @@
+//! Process the series of large query results one at a time.
+//!
+//! [crate::Client::query] and [crate::Client::query_range] collect the whole result in memory.
+//! For queries that return a huge number of series or samples, [crate::Client::query_stream]
+//! and [crate::Client::query_range_stream] decode the response body while it is received and
+//! yield the series one by one, so only a single series needs to be held in memory at a time.
+//!
+//! ```rust
+//! use prometheus_http_query::{Client, Error};
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let client = Client::default();
+//!
+//!     let mut series = client
+//!         .query_range_stream("rate(node_cpu_seconds_total[5m])", 1623345960, 1623841309, Some("5m"), None)
+//!         .await?;
+//!
+//!     while let Some(range_vector) = series.next().await {
+//!         let range_vector = range_vector?;
+//!         println!("{:?}: {} samples", range_vector.metric(), range_vector.samples().len());
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::error::{Error, ResponseError, UnknownResponseStatus, UnsupportedQueryResultType};
+use futures_util::stream::{self, Stream};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// The series of a query result that are decoded one at a time while the response body
+/// is received, i.e. [crate::response::InstantVector]s or [crate::response::RangeVector]s.
+///
+/// Series are not buffered, so the `resultType` of the response must precede its `result`,
+/// as it does in all responses of Prometheus. Otherwise decoding fails with
+/// [Error::MissingField]. [crate::Client::query] and [crate::Client::query_range] accept
+/// either order.
+#[derive(Debug)]
+pub struct SeriesStream<T> {
+    response: Option<reqwest::Response>,
+    decoder: Decoder,
+    finished: bool,
+    series: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> SeriesStream<T> {
+    pub(crate) fn new(response: reqwest::Response, result_type: &'static str) -> Self {
+        SeriesStream {
+            response: Some(response),
+            decoder: Decoder::new(result_type),
+            finished: false,
+            series: PhantomData,
+        }
+    }
+
+    /// Get the next series of the result, or `None` after the last one (or an error)
+    /// was returned.
+    pub async fn next(&mut self) -> Option<Result<T, Error>> {
+        if self.finished {
+            return None;
+        }
+
+        let next = self.advance().await;
+
+        if !matches!(next, Some(Ok(_))) {
+            self.finished = true;
+        }
+
+        next
+    }
+
+    async fn advance(&mut self) -> Option<Result<T, Error>> {
+        loop {
+            match self.decoder.next_series() {
+                Ok(Some(series)) => return Some(Ok(series)),
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+
+            let response = match self.response.as_mut() {
+                Some(response) => response,
+                None => return self.decoder.finish().err().map(Err),
+            };
+
+            match response.chunk().await {
+                Ok(Some(chunk)) => self.decoder.push(&chunk),
+                Ok(None) => self.response = None,
+                Err(e) => return Some(Err(Error::Reqwest(e))),
+            }
+        }
+    }
+
+    /// Get the warnings of the response, e.g. about results truncated by a limit.
+    /// Note that warnings may follow the result, so this list is only complete once
+    /// all series have been consumed.
+    pub fn warnings(&self) -> &[String] {
+        &self.decoder.warnings
+    }
+
+    /// Convert this into a [Stream] of series, e.g. to use the combinators of the
+    /// `futures` crate.
+    pub fn into_stream(self) -> impl Stream<Item = Result<T, Error>> {
+        stream::unfold(self, |mut series| async move {
+            series.next().await.map(|next| (next, series))
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Phase {
+    Prefix,
+    Result,
+    Suffix,
+}
+
+// An incremental scanner of the JSON envelope of query responses. It keeps track of the
+// nesting of the document and the keys of the first two levels in order to find the
+// `data.result` array, and only buffers the bytes of the series it is currently decoding.
+#[derive(Debug)]
+struct Decoder {
+    buffer: Vec<u8>,
+    pos: usize,
+    // The opening brackets of the containers that enclose the current position.
+    stack: Vec<u8>,
+    // The current keys of the top-level object and of the `data` object.
+    keys: [Option<String>; 2],
+    key: Option<String>,
+    after_colon: bool,
+    string_start: Option<usize>,
+    string_is_value: bool,
+    escaped: bool,
+    series_start: Option<usize>,
+    phase: Phase,
+    expected: &'static str,
+    result_type: Option<String>,
+    status: Option<String>,
+    error_type: Option<String>,
+    error: Option<String>,
+    warnings: Vec<String>,
+}
+
+impl Decoder {
+    fn new(expected: &'static str) -> Self {
+        Decoder {
+            buffer: vec![],
+            pos: 0,
+            stack: vec![],
+            keys: [None, None],
+            key: None,
+            after_colon: false,
+            string_start: None,
+            string_is_value: false,
+            escaped: false,
+            series_start: None,
+            phase: Phase::Prefix,
+            expected,
+            result_type: None,
+            status: None,
+            error_type: None,
+            error: None,
+            warnings: vec![],
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    // Scan the buffered bytes and decode the next series once it is complete.
+    fn next_series<T: DeserializeOwned>(&mut self) -> Result<Option<T>, Error> {
+        while self.pos < self.buffer.len() {
+            let i = self.pos;
+            let byte = self.buffer[i];
+            self.pos += 1;
+
+            if let Some(start) = self.string_start {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.string_start = None;
+                    // Strings within the series are decoded along with the series.
+                    if self.stack.len() <= 2 {
+                        let string: String = serde_json::from_slice(&self.buffer[start..=i])
+                            .map_err(Error::ResponseParse)?;
+                        self.on_string(string);
+                    }
+                }
+                continue;
+            }
+
+            match byte {
+                b' ' | b'\t' | b'\n' | b'\r' => continue,
+                b'"' => {
+                    self.string_start = Some(i);
+                    self.string_is_value = self.after_colon || self.stack.last() == Some(&b'[');
+                }
+                b':' => {
+                    let depth = self.stack.len();
+                    if let Some(key) = self.key.take() {
+                        if (1..=2).contains(&depth) {
+                            self.keys[depth - 1] = Some(key);
+                        }
+                    }
+                    self.after_colon = true;
+                    continue;
+                }
+                b'{' | b'[' => {
+                    if self.phase == Phase::Result && self.stack.len() == 3 {
+                        self.series_start = Some(i);
+                    } else if self.phase == Phase::Prefix
+                        && byte == b'['
+                        && self.after_colon
+                        && self.stack.len() == 2
+                        && self.keys[0].as_deref() == Some("data")
+                        && self.keys[1].as_deref() == Some("result")
+                    {
+                        self.check_result_type()?;
+                        self.phase = Phase::Result;
+                    }
+                    self.stack.push(byte);
+                }
+                b'}' | b']' => {
+                    self.stack.pop();
+                    if self.phase == Phase::Result {
+                        if self.stack.len() == 3 {
+                            if let Some(start) = self.series_start.take() {
+                                let series = serde_json::from_slice(&self.buffer[start..=i])
+                                    .map_err(Error::ResponseParse)?;
+                                self.consume();
+                                return Ok(Some(series));
+                            }
+                        } else if self.stack.len() == 2 {
+                            self.phase = Phase::Suffix;
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            self.after_colon = false;
+        }
+
+        self.consume();
+        Ok(None)
+    }
+
+    fn on_string(&mut self, string: String) {
+        if !self.string_is_value {
+            self.key = Some(string);
+            return;
+        }
+
+        let top_level = self.keys[0].as_deref();
+
+        match (self.stack.len(), self.stack.last()) {
+            (1, _) => match top_level {
+                Some("status") => self.status = Some(string),
+                Some("errorType") => self.error_type = Some(string),
+                Some("error") => self.error = Some(string),
+                _ => {}
+            },
+            (2, Some(b'{'))
+                if top_level == Some("data") && self.keys[1].as_deref() == Some("resultType") =>
+            {
+                self.result_type = Some(string);
+            }
+            (2, Some(b'[')) if top_level == Some("warnings") => self.warnings.push(string),
+            _ => {}
+        }
+    }
+
+    // Only vectors can be streamed as instant vectors and matrices as range vectors.
+    fn check_result_type(&self) -> Result<(), Error> {
+        match self.result_type.as_deref() {
+            Some(result_type) if result_type == self.expected => Ok(()),
+            Some(result_type) => Err(Error::UnsupportedQueryResultType(
+                UnsupportedQueryResultType(result_type.to_string()),
+            )),
+            None => Err(Error::MissingField),
+        }
+    }
+
+    // Drop the bytes that were scanned already and are not part of an incomplete series
+    // or string.
+    fn consume(&mut self) {
+        let keep = [self.series_start, self.string_start]
+            .iter()
+            .flatten()
+            .fold(self.pos, |keep, start| keep.min(*start));
+
+        self.buffer.drain(..keep);
+        self.pos -= keep;
+        self.series_start = self.series_start.map(|start| start - keep);
+        self.string_start = self.string_start.map(|start| start - keep);
+    }
+
+    // Check the status of the response once the whole body was scanned.
+    fn finish(&self) -> Result<(), Error> {
+        match self.status.as_deref() {
+            Some("success") => {}
+            Some("error") => {
+                return Err(Error::ResponseError(ResponseError {
+                    kind: self.error_type.clone().ok_or(Error::MissingField)?,
+                    message: self.error.clone().ok_or(Error::MissingField)?,
+                }))
+            }
+            Some(status) => {
+                return Err(Error::UnknownResponseStatus(UnknownResponseStatus(
+                    status.to_string(),
+                )))
+            }
+            None => return Err(Error::MissingField),
+        }
+
+        if self.phase != Phase::Suffix {
+            return Err(Error::MissingField);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::{InstantVector, RangeVector};
+
+    // Feed the body to the decoder in chunks of the given size and collect all series.
+    fn decode<T: DeserializeOwned>(
+        body: &str,
+        chunk_size: usize,
+        result_type: &'static str,
+    ) -> Result<(Vec<T>, Vec<String>), Error> {
+        let mut decoder = Decoder::new(result_type);
+        let mut series = vec![];
+
+        for chunk in body.as_bytes().chunks(chunk_size) {
+            decoder.push(chunk);
+            while let Some(next) = decoder.next_series()? {
+                series.push(next);
+            }
+        }
+
+        decoder.finish()?;
+
+        Ok((series, decoder.warnings))
+    }
+
+    #[test]
+    fn test_decode_matrix() {
+        let body = r#"{
+            "status": "success",
+            "warnings": ["first \"warning\""],
+            "data": {
+                "resultType": "matrix",
+                "result": [
+                    {"metric": {"__name__": "up", "job": "prometheus", "odd": "]}\\"}, "values": [[1435781430.781, "1"], [1435781445.781, "1"]]},
+                    {"metric": {"__name__": "up", "job": "node"}, "values": [[1435781430.781, "0"]]}
+                ],
+                "stats": {"timings": {"evalTotalTime": 0.1}}
+            },
+            "warnings": ["results truncated due to limit"]
+        }"#;
+
+        for chunk_size in [1, 7, body.len()] {
+            let (series, warnings) = decode::<RangeVector>(body, chunk_size, "matrix").unwrap();
+
+            assert_eq!(series.len(), 2);
+            assert_eq!(series[0].metric().get("odd").unwrap(), "]}\\");
+            assert_eq!(series[0].samples().len(), 2);
+            assert_eq!(series[1].metric().get("job").unwrap(), "node");
+            assert_eq!(
+                warnings,
+                vec!["first \"warning\"", "results truncated due to limit"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_vector() {
+        let body = r#"{"status":"success","data":{"resultType":"vector","result":[{"metric":{"job":"node"},"value":[1435781451.781,"1"]}]}}"#;
+
+        let (series, _) = decode::<InstantVector>(body, 3, "vector").unwrap();
+
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].sample().unwrap().value(), 1.0);
+
+        let (series, _) = decode::<InstantVector>(
+            r#"{"status":"success","data":{"resultType":"vector","result":[]}}"#,
+            3,
+            "vector",
+        )
+        .unwrap();
+
+        assert!(series.is_empty());
+    }
+
+    #[test]
+    fn test_decode_for_error() {
+        let body =
+            r#"{"status":"success","data":{"resultType":"scalar","result":[1435781451.781,"1"]}}"#;
+
+        assert!(matches!(
+            decode::<RangeVector>(body, 5, "matrix"),
+            Err(Error::UnsupportedQueryResultType(_))
+        ));
+
+        let body =
+            r#"{"status":"error","errorType":"bad_data","error":"invalid parameter \"query\""}"#;
+
+        match decode::<RangeVector>(body, 5, "matrix") {
+            Err(Error::ResponseError(e)) => {
+                assert_eq!(e.kind, "bad_data");
+                assert_eq!(e.message, "invalid parameter \"query\"");
+            }
+            _ => panic!("expected a response error"),
+        }
+
+        let body = r#"{"status":"success","data":{"resultType":"matrix","result":[{"metric":{}"#;
+
+        assert!(matches!(
+            decode::<RangeVector>(body, 5, "matrix"),
+            Err(Error::MissingField)
+        ));
+
+        // The result type must precede the result.
+        let body = r#"{"status":"success","data":{"result":[{"metric":{},"values":[]}],"resultType":"matrix"}}"#;
+
+        assert!(matches!(
+            decode::<RangeVector>(body, 5, "matrix"),
+            Err(Error::MissingField)
+        ));
+    }
+}
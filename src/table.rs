@@ -0,0 +1,421 @@
+//! Align the time series of a range query result onto a shared grid of timestamps.
+//!
+//! Series of a range query result may have different gaps, e.g. because a target was down
+//! for a while. An [Aligner] pivots them into a [Table] with one row per timestamp and one
+//! [Column] per time series, where missing points are explicitly `None` unless they are
+//! filled (see [Fill]).
+//!
+//! ```rust
+//! use prometheus_http_query::table::{Aligner, Fill};
+//! use prometheus_http_query::{Client, Error};
+//! use std::time::Duration;
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), Error> {
+//!     let client = Client::default();
+//!
+//!     let (start, end) = (1623345960, 1623346260);
+//!
+//!     let result = client.query_range("up", start, end, Some("30s"), None).await?;
+//!
+//!     let mut table = Aligner::new()
+//!         .grid(start, end, Duration::from_secs(30))
+//!         .fill(Fill::Forward)
+//!         .name_by(&["job", "instance"])
+//!         .align(&result)?;
+//!
+//!     // Order the columns by job first, then by instance.
+//!     table.sort_columns_by(&["job", "instance"]);
+//!
+//!     assert_eq!(table.len(), 11);
+//!
+//!     for (timestamp, values) in table.rows() {
+//!         println!("{} {:?}", timestamp, values);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::error::{Error, InvalidFunctionArgument};
+use crate::response::{self, QueryResultType, RangeVector};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::Duration;
+
+/// How to fill points of the grid where a time series has no sample.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Fill {
+    /// Leave missing points empty.
+    #[default]
+    None,
+    /// Repeat the last known value of a time series until the next sample.
+    Forward,
+    /// Interpolate linearly between the surrounding samples of a time series.
+    Linear,
+}
+
+/// Aligns the time series of a range query result onto a shared grid of timestamps.
+#[derive(Debug, Clone, Default)]
+pub struct Aligner {
+    pub(crate) grid: Option<(i64, i64, i64)>,
+    pub(crate) fill: Fill,
+    pub(crate) labels: Vec<String>,
+    pub(crate) separator: String,
+}
+
+impl Aligner {
+    /// Create a new aligner that uses the timestamps of all samples as grid, leaves missing
+    /// points empty and names columns after their whole label set, e.g.
+    /// `up{instance="localhost:9090",job="prometheus"}`.
+    pub fn new() -> Self {
+        Aligner {
+            separator: String::from(","),
+            ..Default::default()
+        }
+    }
+
+    /// Use the grid of timestamps of the range query, i.e. the same start and end (in
+    /// seconds) and step that were passed to [crate::Client::query_range]. Samples that
+    /// are not on this grid are dropped.
+    pub fn grid(mut self, start: i64, end: i64, step: Duration) -> Self {
+        self.grid = Some((start * 1000, end * 1000, step.as_millis() as i64));
+        self
+    }
+
+    /// Set how points where a time series has no sample are filled.
+    pub fn fill(mut self, fill: Fill) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Name columns after the values of the given labels, e.g. `prometheus,localhost:9090`
+    /// for `job` and `instance`. Labels that a time series does not have are empty.
+    pub fn name_by(mut self, labels: &[&str]) -> Self {
+        self.labels = labels.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    /// Set the separator between label values in column names (default: `,`).
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Align a range vector result. Other result types return an error.
+    pub fn align(&self, result: &QueryResultType) -> Result<Table, Error> {
+        match result {
+            QueryResultType::Matrix(series) => self.align_range(series),
+            _ => Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                message: String::from("only results of type 'matrix' can be aligned"),
+            })),
+        }
+    }
+
+    /// Align a set of range vectors, e.g. the result of [crate::Client::query_range].
+    pub fn align_range(&self, series: &[RangeVector]) -> Result<Table, Error> {
+        let timestamps = self.timestamps(series)?;
+
+        let mut names = HashSet::new();
+        let mut columns = vec![];
+
+        for s in series {
+            let name = self.name(s);
+
+            if !names.insert(name.clone()) {
+                return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                    message: format!(
+                        "more than one time series is named '{}', name columns by more labels",
+                        name
+                    ),
+                }));
+            }
+
+            let mut values = vec![None; timestamps.len()];
+
+            for sample in s.samples() {
+                if let Ok(i) = timestamps.binary_search(&sample.timestamp_millis()) {
+                    values[i] = Some(sample.value());
+                }
+            }
+
+            match self.fill {
+                Fill::None => {}
+                Fill::Forward => forward_fill(&mut values),
+                Fill::Linear => linear_fill(&timestamps, &mut values),
+            }
+
+            columns.push(Column {
+                name,
+                labels: s.metric().clone(),
+                values,
+            });
+        }
+
+        Ok(Table {
+            timestamps,
+            columns,
+        })
+    }
+
+    fn timestamps(&self, series: &[RangeVector]) -> Result<Vec<i64>, Error> {
+        match self.grid {
+            Some((start, end, step)) => {
+                if step <= 0 || end < start {
+                    return Err(Error::InvalidFunctionArgument(InvalidFunctionArgument {
+                        message: format!(
+                            "the grid from {} to {} with a step of {}ms is invalid",
+                            start / 1000,
+                            end / 1000,
+                            step
+                        ),
+                    }));
+                }
+                Ok((start..=end).step_by(step as usize).collect())
+            }
+            None => Ok(series
+                .iter()
+                .flat_map(|s| s.samples().iter().map(|s| s.timestamp_millis()))
+                .collect::<BTreeSet<i64>>()
+                .into_iter()
+                .collect()),
+        }
+    }
+
+    fn name(&self, series: &RangeVector) -> String {
+        if self.labels.is_empty() {
+            return response::series_name(series.metric());
+        }
+
+        self.labels
+            .iter()
+            .map(|l| series.metric().get(l).map(|v| v.as_str()).unwrap_or(""))
+            .collect::<Vec<&str>>()
+            .join(&self.separator)
+    }
+}
+
+fn forward_fill(values: &mut [Option<f64>]) {
+    let mut last = None;
+
+    for value in values.iter_mut() {
+        match value {
+            Some(v) => last = Some(*v),
+            None => *value = last,
+        }
+    }
+}
+
+// Points before the first and after the last sample are left empty.
+fn linear_fill(timestamps: &[i64], values: &mut [Option<f64>]) {
+    let mut previous: Option<usize> = None;
+
+    for i in 0..values.len() {
+        if values[i].is_none() {
+            continue;
+        }
+
+        if let Some(p) = previous.filter(|p| i - p > 1) {
+            let (t0, v0) = (timestamps[p], values[p].unwrap());
+            let (t1, v1) = (timestamps[i], values[i].unwrap());
+
+            for j in p + 1..i {
+                let ratio = (timestamps[j] - t0) as f64 / (t1 - t0) as f64;
+                values[j] = Some(v0 + (v1 - v0) * ratio);
+            }
+        }
+
+        previous = Some(i);
+    }
+}
+
+/// A single time series of a [Table] with one value per timestamp of the table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub(crate) name: String,
+    pub(crate) labels: HashMap<String, String>,
+    pub(crate) values: Vec<Option<f64>>,
+}
+
+impl Column {
+    /// Get the name of this column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the labels (+ metric name) of the time series.
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// Get the values of this column, `None` where the time series has no (filled) value.
+    pub fn values(&self) -> &[Option<f64>] {
+        &self.values
+    }
+}
+
+/// Time series aligned onto a shared grid of timestamps with one row per timestamp and
+/// one [Column] per time series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub(crate) timestamps: Vec<i64>,
+    pub(crate) columns: Vec<Column>,
+}
+
+impl Table {
+    /// Get the timestamps of all rows in milliseconds.
+    pub fn timestamps(&self) -> &[i64] {
+        &self.timestamps
+    }
+
+    /// Get all columns.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Get a column by its name.
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Get the number of rows.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Check whether this table contains no rows.
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Get the values of all columns at a timestamp (in milliseconds).
+    pub fn row(&self, timestamp: i64) -> Option<Vec<Option<f64>>> {
+        let i = self.timestamps.binary_search(&timestamp).ok()?;
+        Some(self.columns.iter().map(|c| c.values[i]).collect())
+    }
+
+    /// Iterate over all rows as pairs of timestamp (in milliseconds) and the values of all
+    /// columns.
+    pub fn rows(&self) -> impl Iterator<Item = (i64, Vec<Option<f64>>)> + '_ {
+        self.timestamps
+            .iter()
+            .enumerate()
+            .map(move |(i, t)| (*t, self.columns.iter().map(|c| c.values[i]).collect()))
+    }
+
+    /// Sort the columns by name.
+    pub fn sort_columns(&mut self) {
+        self.columns.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Sort the columns by the values of the given labels (missing labels first) and then
+    /// by name.
+    pub fn sort_columns_by(&mut self, labels: &[&str]) {
+        self.columns.sort_by(|a, b| {
+            let key = |c: &Column| -> Vec<Option<String>> {
+                labels.iter().map(|l| c.labels.get(*l).cloned()).collect()
+            };
+            key(a).cmp(&key(b)).then_with(|| a.name.cmp(&b.name))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{matrix, range_vector};
+    use crate::response::Sample;
+
+    const START: i64 = 1435781430;
+
+    #[test]
+    fn test_align_on_grid() {
+        let table = Aligner::new()
+            .grid(START, START + 45, Duration::from_secs(15))
+            .align_range(&matrix())
+            .unwrap();
+
+        assert_eq!(
+            table.timestamps(),
+            [1435781430000, 1435781445000, 1435781460000, 1435781475000]
+        );
+        assert_eq!(
+            table.columns()[0].name(),
+            r#"up{instance="a:9100",job="node"}"#
+        );
+        assert_eq!(
+            table.columns()[0].values(),
+            [Some(1.0), Some(0.0), None, Some(1.0)]
+        );
+        assert_eq!(table.row(1435781475000).unwrap(), vec![Some(1.0), None]);
+        assert_eq!(table.row(1435781450000), None);
+    }
+
+    #[test]
+    fn test_align_with_fill() {
+        let table = Aligner::new()
+            .fill(Fill::Forward)
+            .name_by(&["job"])
+            .align_range(&matrix())
+            .unwrap();
+
+        assert_eq!(
+            table.timestamps(),
+            [1435781430000, 1435781445000, 1435781475000]
+        );
+        assert_eq!(
+            table.column("node").unwrap().values(),
+            [Some(1.0), Some(0.0), Some(1.0)]
+        );
+        assert_eq!(
+            table.column("prometheus").unwrap().values(),
+            [None, Some(1.0), Some(1.0)]
+        );
+
+        let table = Aligner::new()
+            .grid(START - 15, START + 60, Duration::from_secs(15))
+            .fill(Fill::Linear)
+            .name_by(&["job"])
+            .align_range(&matrix())
+            .unwrap();
+
+        assert_eq!(
+            table.column("node").unwrap().values(),
+            [None, Some(1.0), Some(0.0), Some(0.5), Some(1.0), None]
+        );
+    }
+
+    #[test]
+    fn test_sort_columns() {
+        let mut series = matrix();
+        series.push(range_vector("blackbox", Some("c:9115"), &[]));
+
+        let mut table = Aligner::new()
+            .name_by(&["job", "instance"])
+            .separator("/")
+            .align_range(&series)
+            .unwrap();
+
+        table.sort_columns();
+
+        let names: Vec<&str> = table.columns().iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["blackbox/c:9115", "node/a:9100", "prometheus/"]);
+
+        table.sort_columns_by(&["instance"]);
+
+        let names: Vec<&str> = table.columns().iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["prometheus/", "node/a:9100", "blackbox/c:9115"]);
+    }
+
+    #[test]
+    fn test_align_for_error() {
+        let aligner = Aligner::new().name_by(&["__name__"]);
+
+        assert!(aligner.align_range(&matrix()).is_err());
+        assert!(Aligner::new()
+            .grid(START + 45, START, Duration::from_secs(15))
+            .align_range(&matrix())
+            .is_err());
+        assert!(Aligner::new()
+            .align(&QueryResultType::Scalar(Sample::new(1000.0, 1.0)))
+            .is_err());
+    }
+}
@@ -0,0 +1,98 @@
+// A minimal HTTP server for tests that records all requests and responds with fixed
+// statuses and a fixed body.
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+pub(crate) struct Request {
+    pub(crate) request_line: String,
+    pub(crate) headers: String,
+    pub(crate) body: Vec<u8>,
+}
+
+// Formats the request line and the body separated by a line feed.
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\n{}",
+            self.request_line,
+            String::from_utf8_lossy(&self.body)
+        )
+    }
+}
+
+pub(crate) type Requests = Arc<Mutex<Vec<Request>>>;
+
+// Start a server that responds to all requests with the given status and body and
+// return its URL.
+pub(crate) async fn start(status: u16, body: &'static str) -> (String, Requests) {
+    start_with_statuses(vec![status], body).await
+}
+
+// Start a server that responds with the given statuses in order (and with the last one
+// from then on).
+pub(crate) async fn start_with_statuses(
+    statuses: Vec<u16>,
+    body: &'static str,
+) -> (String, Requests) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(vec![]));
+    let store = received.clone();
+
+    tokio::spawn(async move {
+        let mut count = 0;
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![];
+            let mut chunk = [0u8; 4096];
+
+            let header_end = loop {
+                let n = stream.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..n]);
+                if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break i + 4;
+                }
+            };
+
+            let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+            let length: usize = headers
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length: "))
+                .map(|l| l.trim().parse().unwrap())
+                .unwrap_or(0);
+
+            while buf.len() < header_end + length {
+                let n = stream.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..n]);
+            }
+
+            let request_line = String::from_utf8_lossy(&buf)
+                .lines()
+                .next()
+                .unwrap()
+                .to_string();
+
+            store.lock().unwrap().push(Request {
+                request_line,
+                headers,
+                body: buf[header_end..].to_vec(),
+            });
+
+            let status = statuses[count.min(statuses.len() - 1)];
+            count += 1;
+
+            let response = format!(
+                "HTTP/1.1 {} Status\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    (url, received)
+}
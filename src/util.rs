@@ -1,5 +1,6 @@
 use crate::error::Error;
-use serde::Deserialize;
+use crate::selector::Selector;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// A helper type that provides label matching logic for e.g. aggregations like `sum`.<br>
@@ -75,13 +76,14 @@ impl fmt::Display for TargetState {
 }
 
 /// A helper type to represent possible target health states.
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TargetHealth {
-    #[serde(alias = "up")]
+    #[serde(rename = "up")]
     Up,
-    #[serde(alias = "down")]
+    #[serde(rename = "down")]
     Down,
-    #[serde(alias = "unknown")]
+    #[serde(rename = "unknown")]
+    #[serde(other)]
     Unknown,
 }
 
@@ -96,13 +98,14 @@ impl fmt::Display for TargetHealth {
 }
 
 /// A helper type to represent possible rule health states.
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum RuleHealth {
-    #[serde(alias = "ok")]
+    #[serde(rename = "ok")]
     Good,
-    #[serde(alias = "err")]
+    #[serde(rename = "err")]
     Bad,
-    #[serde(alias = "unknown")]
+    #[serde(rename = "unknown")]
+    #[serde(other)]
     Unknown,
 }
 
@@ -117,13 +120,13 @@ impl fmt::Display for RuleHealth {
 }
 
 /// A helper type to represent possible rule health states.
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AlertState {
-    #[serde(alias = "inactive")]
+    #[serde(rename = "inactive")]
     Inactive,
-    #[serde(alias = "pending")]
+    #[serde(rename = "pending")]
     Pending,
-    #[serde(alias = "firing")]
+    #[serde(rename = "firing")]
     Firing,
 }
 
@@ -138,7 +141,7 @@ impl fmt::Display for AlertState {
 }
 
 /// A helper type to filter rules by type.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum RuleType {
     Alert,
     Record,
@@ -153,6 +156,84 @@ impl fmt::Display for RuleType {
     }
 }
 
+/// A helper type to filter rules and paginate rule groups (see [crate::Client::rules_filtered]).
+///
+/// ```rust
+/// use prometheus_http_query::{RuleFilter, RuleType, Selector};
+///
+/// let filter = RuleFilter::new()
+///     .rule_type(RuleType::Alert)
+///     .file("/etc/prometheus/rules/node.yml")
+///     .selector(Selector::new().with("severity", "critical"))
+///     .group_limit(10);
+/// ```
+#[derive(Debug, Default)]
+pub struct RuleFilter<'a> {
+    pub(crate) rule_type: Option<RuleType>,
+    pub(crate) rule_names: Vec<&'a str>,
+    pub(crate) rule_groups: Vec<&'a str>,
+    pub(crate) files: Vec<&'a str>,
+    pub(crate) exclude_alerts: bool,
+    pub(crate) selectors: Vec<Selector<'a>>,
+    pub(crate) group_limit: Option<usize>,
+    pub(crate) group_next_token: Option<&'a str>,
+}
+
+impl<'a> RuleFilter<'a> {
+    /// Create a filter that matches all rules.
+    pub fn new() -> Self {
+        RuleFilter::default()
+    }
+
+    /// Only return alerting or recording rules.
+    pub fn rule_type(mut self, rule_type: RuleType) -> Self {
+        self.rule_type = Some(rule_type);
+        self
+    }
+
+    /// Only return rules with this name. May be called multiple times.
+    pub fn rule_name(mut self, name: &'a str) -> Self {
+        self.rule_names.push(name);
+        self
+    }
+
+    /// Only return rules of the group with this name. May be called multiple times.
+    pub fn rule_group(mut self, group: &'a str) -> Self {
+        self.rule_groups.push(group);
+        self
+    }
+
+    /// Only return rules defined in this file. May be called multiple times.
+    pub fn file(mut self, file: &'a str) -> Self {
+        self.files.push(file);
+        self
+    }
+
+    /// Omit the active alerts of alerting rules.
+    pub fn exclude_alerts(mut self, exclude: bool) -> Self {
+        self.exclude_alerts = exclude;
+        self
+    }
+
+    /// Only return rules whose labels match this selector. May be called multiple times.
+    pub fn selector(mut self, selector: Selector<'a>) -> Self {
+        self.selectors.push(selector);
+        self
+    }
+
+    /// Return at most this many rule groups per request (see [crate::response::RuleGroups::next_token]).
+    pub fn group_limit(mut self, limit: usize) -> Self {
+        self.group_limit = Some(limit);
+        self
+    }
+
+    /// Continue with the page of rule groups identified by this token.
+    pub fn group_next_token(mut self, token: &'a str) -> Self {
+        self.group_next_token = Some(token);
+        self
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum Label<'c> {
     With((&'c str, &'c str)),
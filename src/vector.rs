@@ -1243,3 +1243,47 @@ impl TryFrom<Selector<'_>> for RangeVector {
         Ok(RangeVector(selector.to_string()))
     }
 }
+
+/// A string literal expression that can be passed to a [crate::Client] in order to evaluate.
+///
+/// Double quotes and backslashes in the original string are escaped so that the
+/// resulting expression is always a valid PromQL string literal.
+#[derive(Debug, PartialEq)]
+pub struct StringLiteral(pub String);
+
+impl fmt::Display for StringLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let StringLiteral(s) = self;
+        write!(f, "{}", s)
+    }
+}
+
+impl From<&str> for StringLiteral {
+    /// Convert a string to a [StringLiteral].
+    ///
+    /// ```rust
+    /// use prometheus_http_query::StringLiteral;
+    ///
+    /// let s = StringLiteral::from(r#"some "quoted" string"#);
+    ///
+    /// assert_eq!(s.to_string(), r#""some \"quoted\" string""#);
+    /// ```
+    fn from(literal: &str) -> Self {
+        let mut s = String::with_capacity(literal.len() + 2);
+
+        s.push('"');
+
+        for c in literal.chars() {
+            match c {
+                '"' => s.push_str("\\\""),
+                '\\' => s.push_str("\\\\"),
+                '\n' => s.push_str("\\n"),
+                _ => s.push(c),
+            }
+        }
+
+        s.push('"');
+
+        StringLiteral(s)
+    }
+}
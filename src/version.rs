@@ -0,0 +1,336 @@
+use crate::error::{Error, UnsupportedFunction};
+use std::fmt;
+use std::str::FromStr;
+
+/// The version of a Prometheus server, e.g. as reported by [crate::Client::build_information].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub(crate) major: u32,
+    pub(crate) minor: u32,
+    pub(crate) patch: u32,
+}
+
+impl Version {
+    /// Create a new version.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Get the major version.
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    /// Get the minor version.
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+
+    /// Get the patch version.
+    pub fn patch(&self) -> u32 {
+        self.patch
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    /// Parse a version like `2.31.0`, `v3.0.0-rc.1` or `2.53.1+dedupelabels`. Pre-release
+    /// and build metadata are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            Error::InvalidFunctionArgument(crate::error::InvalidFunctionArgument {
+                message: format!("'{}' is not a valid Prometheus version", s),
+            })
+        };
+
+        let core = s
+            .trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default();
+
+        let mut parts = core.split('.').map(|p| p.parse::<u32>());
+
+        let major = parts.next().and_then(|p| p.ok()).ok_or_else(invalid)?;
+        let minor = parts.next().and_then(|p| p.ok()).ok_or_else(invalid)?;
+        let patch = match parts.next() {
+            Some(p) => p.map_err(|_| invalid())?,
+            None => 0,
+        };
+
+        Ok(Version::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// PromQL functions that are not available in all supported server versions, along with the
+// version they were introduced in and the version they were removed in (if any).
+const FUNCTIONS: &[(&str, Version, Option<Version>)] = &[
+    ("present_over_time", Version::new(2, 29, 0), None),
+    ("acos", Version::new(2, 31, 0), None),
+    ("acosh", Version::new(2, 31, 0), None),
+    ("asin", Version::new(2, 31, 0), None),
+    ("asinh", Version::new(2, 31, 0), None),
+    ("atan", Version::new(2, 31, 0), None),
+    ("atanh", Version::new(2, 31, 0), None),
+    ("cos", Version::new(2, 31, 0), None),
+    ("cosh", Version::new(2, 31, 0), None),
+    ("sin", Version::new(2, 31, 0), None),
+    ("sinh", Version::new(2, 31, 0), None),
+    ("tan", Version::new(2, 31, 0), None),
+    ("tanh", Version::new(2, 31, 0), None),
+    ("deg", Version::new(2, 31, 0), None),
+    ("rad", Version::new(2, 31, 0), None),
+    ("pi", Version::new(2, 31, 0), None),
+    ("histogram_count", Version::new(2, 40, 0), None),
+    ("histogram_sum", Version::new(2, 40, 0), None),
+    ("histogram_fraction", Version::new(2, 40, 0), None),
+    ("histogram_avg", Version::new(2, 49, 0), None),
+    ("sort_by_label", Version::new(2, 49, 0), None),
+    ("sort_by_label_desc", Version::new(2, 49, 0), None),
+    ("mad_over_time", Version::new(2, 49, 0), None),
+    ("histogram_stddev", Version::new(2, 50, 0), None),
+    ("histogram_stdvar", Version::new(2, 50, 0), None),
+    ("limitk", Version::new(2, 54, 0), None),
+    ("limit_ratio", Version::new(2, 54, 0), None),
+    ("double_exponential_smoothing", Version::new(3, 0, 0), None),
+    ("info", Version::new(3, 0, 0), None),
+    ("ts_of_min_over_time", Version::new(3, 5, 0), None),
+    ("ts_of_max_over_time", Version::new(3, 5, 0), None),
+    ("ts_of_last_over_time", Version::new(3, 5, 0), None),
+    (
+        "holt_winters",
+        Version::new(2, 0, 0),
+        Some(Version::new(3, 0, 0)),
+    ),
+];
+
+/// The set of features a Prometheus server supports, derived from its version
+/// (see [crate::Client::detect_capabilities]). These are used to check expressions only.
+/// Responses are deliberately not parsed depending on the version. Instead, response fields
+/// that only some supported versions return are [Option]s, so that responses of all
+/// versions parse the same way, even if the version of the server is unknown.
+///
+/// ```rust
+/// use prometheus_http_query::{Capabilities, Version};
+///
+/// let capabilities = Capabilities::new(Version::new(2, 26, 0));
+///
+/// assert!(capabilities.supports_function("rate"));
+/// assert!(!capabilities.supports_function("sin"));
+/// assert!(capabilities.check("sum by (job) (rate(up[5m]))").is_ok());
+/// assert!(capabilities.check("sin(pi() * up)").is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub(crate) version: Version,
+}
+
+impl Capabilities {
+    /// Create the capabilities of a server of the given version.
+    pub fn new(version: Version) -> Self {
+        Capabilities { version }
+    }
+
+    // Derive the capabilities from the version reported in the build information, unless
+    // it is no Prometheus version, e.g. of Thanos.
+    pub(crate) fn from_build_version(version: &str) -> Option<Self> {
+        Version::from_str(version)
+            .ok()
+            .filter(|v| v.major >= 2)
+            .map(Capabilities::new)
+    }
+
+    /// Get the version of the server.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Check whether the server supports a PromQL function.
+    pub fn supports_function(&self, name: &str) -> bool {
+        self.requirement(name).is_none()
+    }
+
+    /// Check whether the server supports all functions used in a PromQL expression and
+    /// return an [Error::UnsupportedFunction] for the first one that it does not support.
+    pub fn check(&self, expression: &str) -> Result<(), Error> {
+        for function in functions(expression) {
+            if let Some(required) = self.requirement(function) {
+                return Err(Error::UnsupportedFunction(UnsupportedFunction {
+                    function: function.to_string(),
+                    required,
+                    version: self.version.to_string(),
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    // Describe the versions that support a function, if this version does not.
+    fn requirement(&self, name: &str) -> Option<String> {
+        let (_, since, removed) = FUNCTIONS.iter().find(|(n, _, _)| *n == name)?;
+
+        if self.version < *since {
+            return Some(format!(">= {}", since));
+        }
+
+        match removed {
+            Some(removed) if self.version >= *removed => Some(format!("< {}", removed)),
+            _ => None,
+        }
+    }
+}
+
+// Keywords that may be followed by a parenthesis but are no functions.
+const KEYWORDS: &[&str] = &[
+    "by",
+    "without",
+    "on",
+    "ignoring",
+    "group_left",
+    "group_right",
+    "and",
+    "or",
+    "unless",
+    "bool",
+    "offset",
+    "atan2",
+];
+
+// Find the names of all functions and aggregations called in a PromQL expression,
+// i.e. identifiers outside of strings and comments that are followed by a parenthesis.
+fn functions(expression: &str) -> Vec<&str> {
+    let bytes = expression.as_bytes();
+    let mut result = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'' | b'`') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' && quote != b'`' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                let start = i;
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b':')
+                {
+                    i += 1;
+                }
+                let name = &expression[start..i];
+                let rest = expression[i..].trim_start();
+                // Aggregations may be followed by their grouping, e.g. `sum by (job) (...)`.
+                let grouped = ["by", "without"].iter().any(|g| {
+                    rest.strip_prefix(g)
+                        .is_some_and(|r| r.trim_start().starts_with('('))
+                });
+                if (rest.starts_with('(') || grouped) && !KEYWORDS.contains(&name) {
+                    result.push(name);
+                }
+            }
+            b if b.is_ascii_digit() => {
+                // Skip numbers and durations like `5m` so that their units are not
+                // mistaken for identifiers.
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                    i += 1;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_parsing() {
+        assert_eq!(Version::from_str("2.31.0").unwrap(), Version::new(2, 31, 0));
+        assert_eq!(
+            Version::from_str("v3.0.0-rc.1").unwrap(),
+            Version::new(3, 0, 0)
+        );
+        assert_eq!(
+            Version::from_str("2.53.1+dedupelabels").unwrap(),
+            Version::new(2, 53, 1)
+        );
+        assert_eq!(Version::from_str("2.26").unwrap(), Version::new(2, 26, 0));
+        assert!(Version::from_str("main").is_err());
+        assert!(Version::from_str("2.x.0").is_err());
+        assert!(Version::new(2, 9, 0) < Version::new(2, 31, 0));
+    }
+
+    #[test]
+    fn test_functions() {
+        let expression = r#"sum by (job) (rate(http_requests_total{handler=~"sin(.*)"}[5m] offset 1d)) # cos(x)
+            / on (job) group_left max_over_time(up [1h:5m])"#;
+
+        assert_eq!(functions(expression), vec!["sum", "rate", "max_over_time"]);
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let old = Capabilities::new(Version::new(2, 26, 0));
+        let new = Capabilities::new(Version::new(3, 1, 0));
+
+        assert!(old.check("holt_winters(up[1h], 0.5, 0.5)").is_ok());
+        assert!(new.check("holt_winters(up[1h], 0.5, 0.5)").is_err());
+        assert!(new
+            .check("double_exponential_smoothing(up[1h], 0.5, 0.5)")
+            .is_ok());
+
+        let native = Capabilities::new(Version::new(2, 49, 1));
+
+        assert!(native.check("histogram_avg(rate(latency[5m]))").is_ok());
+        assert!(native.check("histogram_stddev(rate(latency[5m]))").is_err());
+        assert!(Capabilities::new(Version::new(2, 50, 0))
+            .check("histogram_stdvar(rate(latency[5m]))")
+            .is_ok());
+
+        match old.check("present_over_time(up[5m])") {
+            Err(Error::UnsupportedFunction(e)) => {
+                assert_eq!(
+                    e.to_string(),
+                    "the function present_over_time requires Prometheus >= 2.29.0, but the server runs 2.26.0"
+                );
+            }
+            _ => panic!("expected an unsupported function"),
+        }
+    }
+
+    #[test]
+    fn test_capabilities_from_build_version() {
+        assert_eq!(
+            Capabilities::from_build_version("2.53.1+dedupelabels").map(|c| c.version()),
+            Some(Version::new(2, 53, 1))
+        );
+        // Thanos reports its own version.
+        assert!(Capabilities::from_build_version("0.34.0").is_none());
+        assert!(Capabilities::from_build_version("unknown").is_none());
+    }
+}